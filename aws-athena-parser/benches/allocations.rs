@@ -0,0 +1,116 @@
+//! Counts the allocations made while parsing rows, to catch error messages being built on
+//! the happy path, and while building rows with `build_map` and `build_map_borrowed`. Run
+//! with `cargo bench --bench allocations`.
+
+use aws_athena_parser::{build_map, build_map_borrowed, FromAthena, HashMap};
+use aws_sdk_athena::types::{ColumnInfo, Datum, ResultSet, ResultSetMetadata, Row};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[allow(dead_code)]
+#[derive(FromAthena)]
+struct Testing {
+    id: i64,
+    name: String,
+    score: f64,
+    payload: Vec<u8>,
+    note: Option<String>,
+}
+
+const ROWS: usize = 10_000;
+
+fn result_set() -> ResultSet {
+    let columns = [
+        ("id", "bigint"),
+        ("name", "varchar"),
+        ("score", "double"),
+        ("payload", "varbinary"),
+        ("note", "varchar"),
+    ]
+    .iter()
+    .map(|(name, ty)| {
+        ColumnInfo::builder()
+            .name(*name)
+            .r#type(*ty)
+            .build()
+            .expect("name and type are set")
+    })
+    .collect();
+
+    let rows = (0..ROWS)
+        .map(|i| {
+            let values = [
+                i.to_string(),
+                format!("name {}", i),
+                format!("{}.5", i),
+                "de ad be ef 00 11 22 33".to_string(),
+                "note".to_string(),
+            ];
+            let data = values
+                .into_iter()
+                .map(|v| Datum::builder().var_char_value(v).build())
+                .collect();
+            Row::builder().set_data(Some(data)).build()
+        })
+        .collect();
+
+    ResultSet::builder()
+        .result_set_metadata(
+            ResultSetMetadata::builder()
+                .set_column_info(Some(columns))
+                .build(),
+        )
+        .set_rows(Some(rows))
+        .build()
+}
+
+/// Runs `f`, printing how long it took and how many allocations it made per row.
+fn measure<T>(label: &str, f: impl FnOnce() -> T) -> T {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+
+    println!(
+        "{}: {} rows in {:?}, {:.1} allocations per row",
+        label,
+        ROWS,
+        elapsed,
+        allocations as f64 / ROWS as f64
+    );
+    result
+}
+
+fn main() {
+    let result_set = result_set();
+
+    let owned = result_set.clone();
+    let rows: Vec<HashMap<String, String>> = measure("build_map", || build_map(owned));
+    measure("build_map_borrowed", || build_map_borrowed(&result_set));
+
+    measure("from_athena", || {
+        for row in rows {
+            Testing::from_athena(row).expect("every row parses");
+        }
+    });
+}
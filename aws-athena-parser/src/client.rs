@@ -0,0 +1,804 @@
+use crate::{column_types, map_rows_iter, FromAthena, ParserOptions};
+use aws_sdk_athena::operation::get_query_results::GetQueryResultsOutput;
+use aws_sdk_athena::types::{
+    QueryExecution, QueryExecutionContext, QueryExecutionState, ResultConfiguration,
+};
+use aws_sdk_athena::Client;
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(feature = "stream")]
+use futures::{Stream, TryStreamExt};
+
+/// A request to start a query execution.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StartQuery {
+    /// The SQL query to run.
+    pub query: String,
+    /// The database the query runs against.
+    pub database: Option<String>,
+    /// The workgroup the query runs in.
+    pub workgroup: Option<String>,
+    /// The S3 location the query results are written to.
+    pub output_location: Option<String>,
+}
+
+/// The subset of the Athena API used by the client helpers.
+///
+/// This is implemented for `aws_sdk_athena::Client` and can be implemented by a
+/// mock client to test code built on the helpers without AWS access.
+pub trait AthenaApi {
+    /// Starts a query execution, returning its query execution id.
+    fn start_query(
+        &self,
+        request: StartQuery,
+    ) -> impl Future<Output = anyhow::Result<String>> + Send;
+
+    /// Returns the result output location configured for a workgroup, if any.
+    fn work_group_output_location(
+        &self,
+        workgroup: &str,
+    ) -> impl Future<Output = anyhow::Result<Option<String>>> + Send;
+
+    /// Returns the details of a query execution, including its current state.
+    fn query_execution(
+        &self,
+        query_execution_id: &str,
+    ) -> impl Future<Output = anyhow::Result<QueryExecution>> + Send;
+
+    /// Fetches a page of results for a query execution.
+    ///
+    /// `next_token` is `None` for the first page, and otherwise the token returned with
+    /// the previous page.
+    fn query_results(
+        &self,
+        query_execution_id: &str,
+        next_token: Option<String>,
+    ) -> impl Future<Output = anyhow::Result<GetQueryResultsOutput>> + Send;
+}
+
+impl AthenaApi for Client {
+    async fn start_query(&self, request: StartQuery) -> anyhow::Result<String> {
+        let output = self
+            .start_query_execution()
+            .query_string(request.query)
+            .set_work_group(request.workgroup)
+            .set_query_execution_context(
+                request
+                    .database
+                    .map(|db| QueryExecutionContext::builder().database(db).build()),
+            )
+            .set_result_configuration(
+                request
+                    .output_location
+                    .map(|loc| ResultConfiguration::builder().output_location(loc).build()),
+            )
+            .send()
+            .await?;
+
+        output
+            .query_execution_id()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::Error::msg("Athena did not return a query execution id!"))
+    }
+
+    async fn work_group_output_location(&self, workgroup: &str) -> anyhow::Result<Option<String>> {
+        let output = self.get_work_group().work_group(workgroup).send().await?;
+
+        Ok(output
+            .work_group()
+            .and_then(|wg| wg.configuration())
+            .and_then(|config| config.result_configuration())
+            .and_then(|result| result.output_location())
+            .map(str::to_string))
+    }
+
+    async fn query_execution(&self, query_execution_id: &str) -> anyhow::Result<QueryExecution> {
+        let output = self
+            .get_query_execution()
+            .query_execution_id(query_execution_id)
+            .send()
+            .await?;
+
+        output
+            .query_execution()
+            .cloned()
+            .ok_or_else(|| anyhow::Error::msg("Athena did not return the query execution!"))
+    }
+
+    async fn query_results(
+        &self,
+        query_execution_id: &str,
+        next_token: Option<String>,
+    ) -> anyhow::Result<GetQueryResultsOutput> {
+        Ok(self
+            .get_query_results()
+            .query_execution_id(query_execution_id)
+            .set_next_token(next_token)
+            .send()
+            .await?)
+    }
+}
+
+/// How long [`run_query`] waits between checks of a running query.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Runs a query and parses all of its results into `T`.
+///
+/// The query is started, its state is checked every 500 milliseconds until it
+/// finishes, and then every page of results is fetched and parsed. The header row
+/// at the start of the first page is skipped.
+///
+/// # Arguments
+///
+/// * `client` - The client used to run the query.
+/// * `database` - The database the query runs against.
+/// * `query` - The SQL query to run.
+/// * `output_location` - The S3 location the query results are written to, if not
+///   configured by the default workgroup.
+///
+/// # Errors
+///
+/// Returns an error if the query could not be started, failed or was cancelled, or if
+/// a page could not be fetched or a row could not be converted.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_athena_parser::client::run_query;
+/// use aws_athena_parser::{anyhow, FromAthena, HashMap};
+///
+/// #[derive(FromAthena)]
+/// struct MyStruct {
+///     my_value: String,
+/// }
+///
+/// # async fn run(client: aws_sdk_athena::Client) -> anyhow::Result<()> {
+/// let rows: Vec<MyStruct> = run_query(&client, "analytics", "SELECT 'a' AS my_value", None).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn run_query<T: FromAthena, C: AthenaApi>(
+    client: &C,
+    database: &str,
+    query: &str,
+    output_location: Option<&str>,
+) -> anyhow::Result<Vec<T>> {
+    let query_execution_id = client
+        .start_query(StartQuery {
+            query: query.to_string(),
+            database: Some(database.to_string()),
+            workgroup: None,
+            output_location: output_location.map(str::to_string),
+        })
+        .await?;
+
+    wait_for_query(client, &query_execution_id).await?;
+    fetch_results(client, &query_execution_id).await
+}
+
+/// Fetches every page of results for a finished query execution and parses them into `T`.
+///
+/// Pages are requested one after the other by their next token until Athena returns
+/// none. The header row Athena returns at the start of the first page is skipped, while
+/// every row of the later pages is parsed. See [`parse_stream`] to parse the rows as
+/// each page arrives instead.
+///
+/// # Arguments
+///
+/// * `client` - The client used to fetch the pages.
+/// * `query_execution_id` - The id of a query execution that has succeeded.
+///
+/// # Errors
+///
+/// Returns an error if a page could not be fetched or a row could not be converted.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_athena_parser::client::fetch_results;
+/// use aws_athena_parser::{anyhow, FromAthena, HashMap};
+///
+/// #[derive(FromAthena)]
+/// struct MyStruct {
+///     my_value: String,
+/// }
+///
+/// # async fn run(client: aws_sdk_athena::Client) -> anyhow::Result<()> {
+/// let rows: Vec<MyStruct> = fetch_results(&client, "query-id").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn fetch_results<T: FromAthena, C: AthenaApi>(
+    client: &C,
+    query_execution_id: &str,
+) -> anyhow::Result<Vec<T>> {
+    let mut rows = Vec::new();
+    let mut next_token = None;
+    loop {
+        let first = next_token.is_none();
+        let output = client.query_results(query_execution_id, next_token).await?;
+
+        if let Some(result_set) = output.result_set() {
+            let types = column_types(result_set, &ParserOptions::default());
+            for row in map_rows_iter(
+                result_set,
+                first,
+                T::athena_positions(),
+                &ParserOptions::default(),
+            ) {
+                rows.push(row.parse(&types)?);
+            }
+        }
+
+        match output.next_token() {
+            Some(token) => next_token = Some(token.to_string()),
+            None => return Ok(rows),
+        }
+    }
+}
+
+/// Fetches and parses every page of results as [`fetch_results`] does, fetching the next
+/// page while the current one is parsed.
+///
+/// Fetched pages are handed over through a channel holding at most `buffer` pages, so a
+/// slow parse holds back fetching rather than buffering the whole result. Each page is
+/// parsed on Tokio's blocking thread pool, leaving the fetching free to continue.
+///
+/// # Arguments
+///
+/// * `client` - The client used to fetch the pages.
+/// * `query_execution_id` - The id of a query execution that has succeeded.
+/// * `buffer` - The most fetched pages waiting to be parsed, at least one.
+///
+/// # Errors
+///
+/// Returns an error if a page could not be fetched or a row could not be converted.
+/// Fetching stops at the first row that could not be converted.
+pub async fn fetch_results_prefetched<T, C>(
+    client: &C,
+    query_execution_id: &str,
+    buffer: usize,
+) -> anyhow::Result<Vec<T>>
+where
+    T: FromAthena + Send + 'static,
+    C: AthenaApi,
+{
+    let (pages, mut received) = tokio::sync::mpsc::channel(buffer.max(1));
+
+    let fetch = async move {
+        let mut next_token = None;
+        loop {
+            let output = client.query_results(query_execution_id, next_token).await?;
+
+            if let Some(result_set) = output.result_set() {
+                // The parsing side hung up after a failed row.
+                if pages.send(result_set.clone()).await.is_err() {
+                    return anyhow::Ok(());
+                }
+            }
+
+            match output.next_token() {
+                Some(token) => next_token = Some(token.to_string()),
+                None => return Ok(()),
+            }
+        }
+    };
+
+    let parse = async move {
+        let mut rows = Vec::new();
+        let mut first = true;
+        while let Some(result_set) = received.recv().await {
+            let skip_header = std::mem::replace(&mut first, false);
+            let parsed = tokio::task::spawn_blocking(move || {
+                let options = ParserOptions::default();
+                let types = column_types(&result_set, &options);
+                map_rows_iter(&result_set, skip_header, T::athena_positions(), &options)
+                    .map(|row| row.parse(&types))
+                    .collect::<anyhow::Result<Vec<T>>>()
+            })
+            .await??;
+            rows.extend(parsed);
+        }
+        anyhow::Ok(rows)
+    };
+
+    let (fetched, parsed) = tokio::join!(fetch, parse);
+    fetched?;
+    parsed
+}
+
+/// Waits for a query execution to finish, returning an error unless it succeeded.
+///
+/// The returned execution is the one that reported the query as succeeded.
+async fn wait_for_query<C: AthenaApi>(
+    client: &C,
+    query_execution_id: &str,
+) -> anyhow::Result<QueryExecution> {
+    loop {
+        let execution = client.query_execution(query_execution_id).await?;
+        let status = execution.status();
+        let reason = status
+            .and_then(|s| s.state_change_reason())
+            .unwrap_or("no reason given");
+
+        match status.and_then(|s| s.state()) {
+            Some(QueryExecutionState::Succeeded) => return Ok(execution),
+            Some(QueryExecutionState::Failed) => {
+                return Err(anyhow::Error::msg(format!(
+                    "Query `{}` failed: {}",
+                    query_execution_id, reason
+                )))
+            }
+            Some(QueryExecutionState::Cancelled) => {
+                return Err(anyhow::Error::msg(format!(
+                    "Query `{}` was cancelled: {}",
+                    query_execution_id, reason
+                )))
+            }
+            _ => tokio::time::sleep(POLL_INTERVAL).await,
+        }
+    }
+}
+
+/// The parsed rows of a query together with its statistics, see [`QueryRunner::run_and_parse`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryResult<T> {
+    /// The parsed rows.
+    pub rows: Vec<T>,
+    /// The statistics Athena reported for the query.
+    pub stats: QueryStats,
+}
+
+/// Statistics of a finished query execution, as reported by `GetQueryExecution`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryStats {
+    /// The number of bytes the query scanned, which Athena bills by.
+    pub data_scanned_bytes: Option<i64>,
+    /// How long the query took to run within the engine, in milliseconds.
+    pub engine_execution_time_ms: Option<i64>,
+    /// The S3 location the query results were written to.
+    pub output_location: Option<String>,
+}
+
+impl QueryStats {
+    /// Reads the statistics of a query execution.
+    fn from_execution(execution: &QueryExecution) -> Self {
+        let statistics = execution.statistics();
+
+        Self {
+            data_scanned_bytes: statistics.and_then(|s| s.data_scanned_in_bytes()),
+            engine_execution_time_ms: statistics.and_then(|s| s.engine_execution_time_in_millis()),
+            output_location: execution
+                .result_configuration()
+                .and_then(|config| config.output_location())
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Lazily fetches and parses the results of a query execution into `T`.
+///
+/// Pages are requested one at a time as the stream is polled, and only the rows of
+/// the current page are held in memory. The header row Athena returns at the start
+/// of the first page is skipped.
+///
+/// # Arguments
+///
+/// * `client` - The client used to fetch the pages.
+/// * `query_execution_id` - The id of a query execution that has succeeded.
+///
+/// # Returns
+///
+/// A stream yielding each parsed row, or the error of a failed fetch or row.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_athena_parser::client::parse_stream;
+/// use aws_athena_parser::{anyhow, FromAthena, HashMap};
+/// use futures::TryStreamExt;
+///
+/// #[derive(FromAthena)]
+/// struct MyStruct {
+///     my_value: String,
+/// }
+///
+/// # async fn run(client: aws_sdk_athena::Client) -> anyhow::Result<()> {
+/// let mut rows = std::pin::pin!(parse_stream::<MyStruct, _>(&client, "query-id"));
+/// while let Some(row) = rows.try_next().await? {
+///     // ( use the row for your purposes )
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "stream")]
+pub fn parse_stream<'a, T, C>(
+    client: &'a C,
+    query_execution_id: &'a str,
+) -> impl Stream<Item = anyhow::Result<T>> + 'a
+where
+    T: FromAthena + 'a,
+    C: AthenaApi,
+{
+    let pages = futures::stream::try_unfold(Some((None, true)), move |page| async move {
+        let Some((next_token, first)) = page else {
+            return Ok(None);
+        };
+
+        let output = client.query_results(query_execution_id, next_token).await?;
+        let (rows, types) = output
+            .result_set()
+            .map(|rs| {
+                let options = ParserOptions::default();
+                (
+                    map_rows_iter(rs, first, T::athena_positions(), &options).collect::<Vec<_>>(),
+                    column_types(rs, &options),
+                )
+            })
+            .unwrap_or_default();
+        let next_page = output.next_token().map(|t| (Some(t.to_string()), false));
+
+        anyhow::Ok(Some(((rows, types), next_page)))
+    });
+
+    pages
+        .map_ok(|(rows, types)| {
+            futures::stream::iter(rows.into_iter().map(move |row| row.parse(&types)))
+        })
+        .try_flatten()
+}
+
+/// Runs queries with a shared database, workgroup and output location.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_athena_parser::client::QueryRunner;
+///
+/// # async fn run(client: aws_sdk_athena::Client) -> anyhow::Result<()> {
+/// let runner = QueryRunner::new(client)
+///     .database("analytics")
+///     .workgroup("reporting");
+/// let query_execution_id = runner.start("SELECT 1").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueryRunner<C = Client> {
+    client: C,
+    database: Option<String>,
+    workgroup: Option<String>,
+    output_location: Option<String>,
+}
+
+impl<C: AthenaApi> QueryRunner<C> {
+    /// Creates a runner using the given client.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            database: None,
+            workgroup: None,
+            output_location: None,
+        }
+    }
+
+    /// Sets the database queries run against.
+    pub fn database(mut self, database: impl Into<String>) -> Self {
+        self.database = Some(database.into());
+        self
+    }
+
+    /// Sets the workgroup queries run in.
+    pub fn workgroup(mut self, workgroup: impl Into<String>) -> Self {
+        self.workgroup = Some(workgroup.into());
+        self
+    }
+
+    /// Sets the S3 location query results are written to.
+    ///
+    /// When unset and a workgroup is given, the workgroup's configured output location is used.
+    pub fn output_location(mut self, output_location: impl Into<String>) -> Self {
+        self.output_location = Some(output_location.into());
+        self
+    }
+
+    /// Starts the given query, returning its query execution id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the workgroup could not be read or the query could not be started.
+    pub async fn start(&self, query: &str) -> anyhow::Result<String> {
+        let output_location = match (&self.output_location, &self.workgroup) {
+            (Some(location), _) => Some(location.clone()),
+            (None, Some(workgroup)) => self.client.work_group_output_location(workgroup).await?,
+            (None, None) => None,
+        };
+
+        self.client
+            .start_query(StartQuery {
+                query: query.to_string(),
+                database: self.database.clone(),
+                workgroup: self.workgroup.clone(),
+                output_location,
+            })
+            .await
+    }
+
+    /// Runs the given query and parses all of its results into `T`, along with the
+    /// statistics of the query.
+    ///
+    /// The query is started as with [`QueryRunner::start`] and then polled and fetched
+    /// as with [`run_query`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query could not be started, failed or was cancelled, or if
+    /// a page could not be fetched or a row could not be converted.
+    pub async fn run_and_parse<T: FromAthena>(
+        &self,
+        query: &str,
+    ) -> anyhow::Result<QueryResult<T>> {
+        let query_execution_id = self.start(query).await?;
+        let execution = wait_for_query(&self.client, &query_execution_id).await?;
+        let rows = fetch_results(&self.client, &query_execution_id).await?;
+
+        Ok(QueryResult {
+            rows,
+            stats: QueryStats::from_execution(&execution),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::HashMap;
+    use aws_sdk_athena::types::{QueryExecutionStatistics, QueryExecutionStatus, ResultSet};
+    use std::sync::Mutex;
+
+    #[derive(FromAthena)]
+    struct Testing {
+        pub test: i64,
+    }
+
+    #[derive(Default)]
+    struct MockClient {
+        started: Mutex<Vec<StartQuery>>,
+        work_group_lookups: Mutex<Vec<String>>,
+        states: Mutex<Vec<QueryExecutionState>>,
+        pages: Vec<ResultSet>,
+        fetch_delay: Duration,
+    }
+
+    impl AthenaApi for MockClient {
+        async fn start_query(&self, request: StartQuery) -> anyhow::Result<String> {
+            self.started.lock().unwrap().push(request);
+            Ok("query-id".to_string())
+        }
+
+        async fn work_group_output_location(
+            &self,
+            workgroup: &str,
+        ) -> anyhow::Result<Option<String>> {
+            self.work_group_lookups
+                .lock()
+                .unwrap()
+                .push(workgroup.to_string());
+            Ok(Some(format!("s3://results/{}/", workgroup)))
+        }
+
+        async fn query_execution(
+            &self,
+            _query_execution_id: &str,
+        ) -> anyhow::Result<QueryExecution> {
+            let mut states = self.states.lock().unwrap();
+            let state = match states.is_empty() {
+                true => QueryExecutionState::Succeeded,
+                false => states.remove(0),
+            };
+
+            Ok(QueryExecution::builder()
+                .status(
+                    QueryExecutionStatus::builder()
+                        .state(state)
+                        .state_change_reason("table not found")
+                        .build(),
+                )
+                .statistics(
+                    QueryExecutionStatistics::builder()
+                        .data_scanned_in_bytes(1024)
+                        .engine_execution_time_in_millis(250)
+                        .build(),
+                )
+                .result_configuration(
+                    ResultConfiguration::builder()
+                        .output_location("s3://results/query-id.csv")
+                        .build(),
+                )
+                .build())
+        }
+
+        async fn query_results(
+            &self,
+            _query_execution_id: &str,
+            next_token: Option<String>,
+        ) -> anyhow::Result<GetQueryResultsOutput> {
+            let page = next_token.map_or(0, |t| t.parse::<usize>().unwrap());
+            tokio::time::sleep(self.fetch_delay).await;
+
+            Ok(GetQueryResultsOutput::builder()
+                .set_result_set(self.pages.get(page).cloned())
+                .set_next_token((page + 1 < self.pages.len()).then(|| (page + 1).to_string()))
+                .build())
+        }
+    }
+
+    fn page(values: &[&str]) -> ResultSet {
+        let rows: Vec<&[&str]> = values.iter().map(std::slice::from_ref).collect();
+        crate::test_util::result_set(&[("test", "bigint")], &rows)
+    }
+
+    #[tokio::test]
+    async fn workgroup_passed_to_start_query() {
+        let runner = QueryRunner::new(MockClient::default())
+            .database("db")
+            .workgroup("reporting");
+
+        let id = runner.start("SELECT 1").await.unwrap();
+        assert_eq!(id, "query-id");
+
+        let started = runner.client.started.lock().unwrap();
+        assert_eq!(
+            started[0],
+            StartQuery {
+                query: "SELECT 1".to_string(),
+                database: Some("db".to_string()),
+                workgroup: Some("reporting".to_string()),
+                output_location: Some("s3://results/reporting/".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn explicit_output_location_skips_workgroup_lookup() {
+        let runner = QueryRunner::new(MockClient::default())
+            .workgroup("reporting")
+            .output_location("s3://explicit/");
+
+        runner.start("SELECT 1").await.unwrap();
+
+        let started = runner.client.started.lock().unwrap();
+        assert_eq!(started[0].workgroup, Some("reporting".to_string()));
+        assert_eq!(
+            started[0].output_location,
+            Some("s3://explicit/".to_string())
+        );
+        assert!(runner.client.work_group_lookups.lock().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn parse_stream_across_pages() {
+        let client = MockClient {
+            pages: vec![page(&["test", "1", "2"]), page(&["3"])],
+            ..Default::default()
+        };
+
+        let res: Vec<Testing> = parse_stream(&client, "query-id")
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            res.iter().map(|r| r.test).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_results_across_pages() {
+        let client = MockClient {
+            pages: vec![page(&["test", "1", "2"]), page(&["3", "4"])],
+            ..Default::default()
+        };
+
+        let res: Vec<Testing> = fetch_results(&client, "query-id").await.unwrap();
+        assert_eq!(
+            res.iter().map(|r| r.test).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert!(client.started.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_results_prefetched_across_pages() {
+        let client = MockClient {
+            pages: vec![page(&["test", "1", "2"]), page(&["3"]), page(&["4", "5"])],
+            fetch_delay: Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        let res: Vec<Testing> = fetch_results_prefetched(&client, "query-id", 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            res.iter().map(|r| r.test).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+
+        let client = MockClient {
+            pages: vec![page(&["test", "1"]), page(&["x"]), page(&["3"])],
+            ..Default::default()
+        };
+        assert_eq!(
+            fetch_results_prefetched::<Testing, _>(&client, "query-id", 1)
+                .await
+                .err()
+                .map(|e| format!("{:#}", e))
+                .unwrap(),
+            "Testing: Invalid integer within result set. `test` could not parse `x`: invalid digit!"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_query_polls_and_fetches_all_pages() {
+        let client = MockClient {
+            states: Mutex::new(vec![
+                QueryExecutionState::Queued,
+                QueryExecutionState::Running,
+            ]),
+            pages: vec![page(&["test", "1", "2"]), page(&["3"])],
+            ..Default::default()
+        };
+
+        let res: Vec<Testing> = run_query(&client, "db", "SELECT 1", Some("s3://results/"))
+            .await
+            .unwrap();
+        assert_eq!(
+            res.iter().map(|r| r.test).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(client.states.lock().unwrap().is_empty());
+
+        let started = client.started.lock().unwrap();
+        assert_eq!(started[0].database, Some("db".to_string()));
+        assert_eq!(
+            started[0].output_location,
+            Some("s3://results/".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn run_query_reports_failed_query() {
+        let client = MockClient {
+            states: Mutex::new(vec![QueryExecutionState::Failed]),
+            ..Default::default()
+        };
+
+        let res = run_query::<Testing, _>(&client, "db", "SELECT 1", None).await;
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            "Query `query-id` failed: table not found"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_and_parse_returns_stats() {
+        let runner = QueryRunner::new(MockClient {
+            pages: vec![page(&["test", "1"])],
+            ..Default::default()
+        });
+
+        let res = runner.run_and_parse::<Testing>("SELECT 1").await.unwrap();
+        assert_eq!(res.rows[0].test, 1);
+        assert_eq!(
+            res.stats,
+            QueryStats {
+                data_scanned_bytes: Some(1024),
+                engine_execution_time_ms: Some(250),
+                output_location: Some("s3://results/query-id.csv".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use crate::{is_header_row, missing_metadata, FromAthena, HashMap};
+use aws_sdk_athena::types::ResultSet;
+
+/// Serializes the rows of the given ResultSet as CSV.
+///
+/// The first line holds the column names from the metadata, followed by one line per row
+/// with values in column order. Values containing a comma, a double quote or a line break
+/// are wrapped in double quotes, with any double quote inside doubled. A SQL null becomes
+/// an empty value. The header row Athena returns ahead of the data on the first page is
+/// not repeated.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be serialized.
+///
+/// # Returns
+///
+/// The serialized rows, each line followed by a newline. An empty string is returned if
+/// the ResultSet contains no metadata and no rows.
+///
+/// # Errors
+///
+/// Returns an error if the ResultSet has rows but no `result_set_metadata`.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::ResultSet;
+/// use aws_athena_parser::rows_to_csv;
+///
+/// let result_set = ResultSet::builder().build();
+/// assert_eq!(rows_to_csv(&result_set).unwrap(), "");
+/// ```
+pub fn rows_to_csv(result_set: &ResultSet) -> anyhow::Result<String> {
+    let Some(meta) = result_set.result_set_metadata() else {
+        if result_set.rows().is_empty() {
+            return Ok(String::new());
+        }
+        return Err(missing_metadata(result_set.rows().len()));
+    };
+
+    let columns: Vec<String> = meta
+        .column_info()
+        .iter()
+        .map(|c| c.name().to_string())
+        .collect();
+
+    let mut csv = line(columns.iter().map(String::as_str));
+
+    let skip = match result_set.rows().first() {
+        Some(first) if is_header_row(first, &columns) => 1,
+        _ => 0,
+    };
+
+    for row in result_set.rows().iter().skip(skip) {
+        csv.push_str(&line(
+            row.data().iter().map(|d| d.var_char_value().unwrap_or("")),
+        ));
+    }
+
+    Ok(csv)
+}
+
+/// Reads CSV, such as the TEXTFILE output of an `UNLOAD`, into rows keyed by `columns`.
+///
+/// The CSV has no header line and holds the values of each row in the order of `columns`.
+/// An unquoted value equal to `null_token`, usually `\N`, is a SQL null and is left out of
+/// the row, so an `Option<T>` field reading it is `None`. A quoted value is always text,
+/// so `""` is an empty string and `"\N"` is the text `\N`. Empty lines are skipped.
+///
+/// # Arguments
+///
+/// * `csv` - The CSV to be read.
+/// * `columns` - The name of each column, in the order the values appear.
+/// * `null_token` - The unquoted value that stands for a SQL null.
+///
+/// # Errors
+///
+/// Returns an error if a quoted value is not closed, or a line does not hold one value per
+/// column.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::csv_to_rows;
+///
+/// let rows = csv_to_rows("1,\\N,\"\"\n", &["id", "name", "note"], "\\N").unwrap();
+/// assert_eq!(rows[0]["id"], "1");
+/// assert!(!rows[0].contains_key("name"));
+/// assert_eq!(rows[0]["note"], "");
+/// ```
+pub fn csv_to_rows(
+    csv: &str,
+    columns: &[&str],
+    null_token: &str,
+) -> anyhow::Result<Vec<HashMap<String, String>>> {
+    let mut rows = Vec::new();
+    let mut values: Vec<Option<String>> = Vec::new();
+    let (mut value, mut quoted, mut in_quotes) = (String::new(), false, false);
+
+    // A final newline is added when missing, so the last line ends like every other.
+    let end = (!csv.ends_with('\n')).then_some('\n');
+    let mut chars = csv.chars().chain(end).peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                value.push('"');
+                chars.next();
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            ',' if !in_quotes => values.push(csv_value(
+                std::mem::take(&mut value),
+                std::mem::take(&mut quoted),
+                null_token,
+            )),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => {}
+            '\n' if !in_quotes => {
+                if values.is_empty() && value.is_empty() && !quoted {
+                    continue;
+                }
+                values.push(csv_value(
+                    std::mem::take(&mut value),
+                    std::mem::take(&mut quoted),
+                    null_token,
+                ));
+
+                if values.len() != columns.len() {
+                    return Err(anyhow::Error::msg(format!(
+                        "Invalid CSV. Row {} has {} values, expected {}!",
+                        rows.len() + 1,
+                        values.len(),
+                        columns.len()
+                    )));
+                }
+
+                rows.push(
+                    columns
+                        .iter()
+                        .zip(values.drain(..))
+                        .filter_map(|(column, value)| Some((column.to_string(), value?)))
+                        .collect(),
+                );
+            }
+            c => value.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(anyhow::Error::msg(format!(
+            "Invalid CSV. Row {} has a quoted value that is not closed!",
+            rows.len() + 1
+        )));
+    }
+
+    Ok(rows)
+}
+
+/// Reads CSV with [`csv_to_rows`] and converts every row into `T` with
+/// [`FromAthena::from_athena`].
+///
+/// # Arguments
+///
+/// * `csv` - The CSV to be read.
+/// * `columns` - The name of each column, in the order the values appear.
+/// * `null_token` - The unquoted value that stands for a SQL null.
+///
+/// # Errors
+///
+/// Returns an error if the CSV could not be read, or the error of the first row that
+/// could not be converted.
+pub fn parse_csv<T: FromAthena>(
+    csv: &str,
+    columns: &[&str],
+    null_token: &str,
+) -> anyhow::Result<Vec<T>> {
+    csv_to_rows(csv, columns, null_token)?
+        .into_iter()
+        .map(T::from_athena)
+        .collect()
+}
+
+/// Returns `None` for an unquoted value equal to `null_token`, otherwise the value.
+fn csv_value(value: String, quoted: bool, null_token: &str) -> Option<String> {
+    (quoted || value != null_token).then_some(value)
+}
+
+/// Joins values into one CSV line, quoting those that need it.
+fn line<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    let mut line = values.map(quote).collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+fn quote(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::build_map;
+    use crate::test_util::result_set;
+
+    #[derive(from_athena_derive::FromAthena, Debug, PartialEq)]
+    struct UnloadTesting {
+        pub id: Option<i64>,
+        pub name: Option<String>,
+        pub note: Option<String>,
+    }
+
+    /// Reads CSV written by `rows_to_csv` back into rows keyed by the header.
+    fn read_csv(csv: &str) -> Vec<HashMap<String, String>> {
+        let mut lines = Vec::new();
+        let (mut fields, mut field, mut quoted) = (Vec::new(), String::new(), false);
+        let mut chars = csv.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = !quoted,
+                ',' if !quoted => fields.push(std::mem::take(&mut field)),
+                '\n' if !quoted => {
+                    fields.push(std::mem::take(&mut field));
+                    lines.push(std::mem::take(&mut fields));
+                }
+                c => field.push(c),
+            }
+        }
+
+        let header = lines.remove(0);
+        lines
+            .into_iter()
+            .map(|values| header.iter().cloned().zip(values).collect())
+            .collect()
+    }
+
+    #[test]
+    fn round_trip_csv() {
+        let rows = result_set(
+            &[("id", "bigint"), ("name", "varchar")],
+            &[
+                &["id", "name"],
+                &["1", "Smith, Jane"],
+                &["2", "say \"hi\"\nbye"],
+            ],
+        );
+
+        let csv = rows_to_csv(&rows).unwrap();
+        assert_eq!(
+            csv,
+            "id,name\n1,\"Smith, Jane\"\n2,\"say \"\"hi\"\"\nbye\"\n"
+        );
+        assert_eq!(read_csv(&csv), build_map(rows)[1..]);
+    }
+
+    #[test]
+    fn read_csv_nulls_and_empty_strings() {
+        let csv = "1,\\N,\"\"\r\n\n\\N,\"\\N\",\"a, \"\"b\"\"\"\n3,,\\N";
+
+        let rows = csv_to_rows(csv, &["id", "name", "note"], "\\N").unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                HashMap::from([
+                    ("id".to_string(), "1".to_string()),
+                    ("note".to_string(), String::new()),
+                ]),
+                HashMap::from([
+                    ("name".to_string(), "\\N".to_string()),
+                    ("note".to_string(), "a, \"b\"".to_string()),
+                ]),
+                HashMap::from([
+                    ("id".to_string(), "3".to_string()),
+                    ("name".to_string(), String::new()),
+                ]),
+            ]
+        );
+
+        let res: Vec<UnloadTesting> = parse_csv(csv, &["id", "name", "note"], "\\N").unwrap();
+        assert_eq!(
+            res[0],
+            UnloadTesting {
+                id: Some(1),
+                name: None,
+                note: Some(String::new()),
+            }
+        );
+        assert_eq!(res[1].id, None);
+        assert_eq!(res[2].note, None);
+    }
+
+    #[test]
+    fn error_read_malformed_csv() {
+        assert_eq!(
+            csv_to_rows("1,2\n3\n", &["id", "name"], "\\N")
+                .unwrap_err()
+                .to_string(),
+            "Invalid CSV. Row 2 has 1 values, expected 2!"
+        );
+        assert_eq!(
+            csv_to_rows("1,\"open\n", &["id", "name"], "\\N")
+                .unwrap_err()
+                .to_string(),
+            "Invalid CSV. Row 1 has a quoted value that is not closed!"
+        );
+    }
+
+    #[test]
+    fn error_csv_without_metadata() {
+        let rows = result_set(&[("id", "bigint")], &[&["1"]]);
+        let no_metadata = ResultSet::builder()
+            .set_rows(Some(rows.rows().to_vec()))
+            .build();
+
+        assert_eq!(
+            rows_to_csv(&no_metadata).unwrap_err().to_string(),
+            "Missing metadata within result set. 1 rows were returned without column information!"
+        );
+    }
+}
@@ -0,0 +1,1567 @@
+//! Conversion helpers used by the code generated from `#[derive(FromAthena)]` and
+//! `#[derive(ToAthena)]`.
+//!
+//! These cover the field types and attributes that need more than a plain `str::parse`,
+//! or more than `to_string` when rendering a value back into Athena's text.
+
+use crate::RowSource;
+use std::collections::HashMap;
+use std::num::{IntErrorKind, ParseIntError};
+use std::str::FromStr;
+
+/// A field type parsed with `str::parse`, the fallback for types `#[derive(FromAthena)]` has
+/// no special handling for.
+///
+/// It is implemented for every `FromStr` type whose error converts into `anyhow::Error`,
+/// and exists so that a field of any other type is reported as such at compile time,
+/// rather than as an error within the generated code.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be parsed from Athena",
+    label = "`{Self}` does not implement `FromStr`",
+    note = "implement `FromStr` for `{Self}`, or parse the field with `#[athena(with = \"...\")]`"
+)]
+pub trait FromAthenaField: Sized {
+    /// Parses the value of a field.
+    fn from_athena_field(value: &str) -> anyhow::Result<Self>;
+}
+
+impl<T> FromAthenaField for T
+where
+    T: FromStr,
+    anyhow::Error: From<T::Err>,
+{
+    fn from_athena_field(value: &str) -> anyhow::Result<Self> {
+        Ok(value.parse::<T>()?)
+    }
+}
+
+/// Returns the error reported when a row has no value for `field`.
+///
+/// Every generated `from_athena` builds its missing column error here, so the message
+/// is defined in one place.
+pub fn missing_field(field: &str) -> anyhow::Error {
+    anyhow::Error::msg(format!(
+        "Missing field within result set. `{}` was not found!",
+        field
+    ))
+}
+
+/// Returns the value of a `#[athena(position = N)]` field.
+///
+/// The value at `index` of `positional` is read when it was given, that is when the row
+/// is parsed from a ResultSet. Otherwise the column is read by name, as for other fields.
+pub fn positional_value<'a, R: RowSource + ?Sized>(
+    row: &'a R,
+    positional: &[Option<&'a str>],
+    index: usize,
+    column: &str,
+) -> Option<&'a str> {
+    match positional.get(index) {
+        Some(value) => *value,
+        None => row.get_value(column),
+    }
+}
+
+/// Parses an integer field, naming the field and the reason in the error.
+///
+/// # Errors
+///
+/// Returns an error naming `field` and the value, which says whether the value overflowed
+/// the integer type or was not an integer at all.
+pub fn parse_int<T>(field: &str, value: &str) -> anyhow::Result<T>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    value.parse::<T>().map_err(|e| {
+        let reason = match e.kind() {
+            IntErrorKind::PosOverflow => "overflow, the value is too large for the field type",
+            IntErrorKind::NegOverflow => "overflow, the value is too small for the field type",
+            IntErrorKind::Empty => "the value is empty",
+            // Unsigned types reject the sign itself as an invalid digit.
+            IntErrorKind::InvalidDigit if is_negative_integer(value) => {
+                "overflow, negative values do not fit the unsigned field type"
+            }
+            _ => "invalid digit",
+        };
+
+        anyhow::Error::msg(format!(
+            "Invalid integer within result set. `{}` could not parse `{}`: {}!",
+            field, value, reason
+        ))
+    })
+}
+
+/// Parses the columns named `prefix` followed by a number, such as `score_1` and `score_2`,
+/// for a `#[athena(collect_prefix = "...")]` field.
+///
+/// The values are ordered by the number after the prefix, so `score_2` comes before
+/// `score_10`. Gaps in the numbering are skipped rather than filled, and columns whose
+/// name continues with anything other than digits, such as `score_avg`, are ignored. A row
+/// without any such column yields an empty `Vec`.
+///
+/// # Errors
+///
+/// Returns an error naming the column of the first value that could not be parsed.
+pub fn collect_prefixed<T: FromAthenaField, R: RowSource + ?Sized>(
+    row: &R,
+    prefix: &str,
+) -> anyhow::Result<Vec<T>> {
+    let mut columns: Vec<(u64, &str, &str)> = crate::row::entries(row)
+        .filter_map(|(column, value)| {
+            let suffix = column.strip_prefix(prefix)?;
+            if suffix.is_empty() || !suffix.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            Some((suffix.parse().ok()?, column, value))
+        })
+        .collect();
+    columns.sort();
+
+    columns
+        .into_iter()
+        .map(|(_, column, value)| {
+            T::from_athena_field(value).map_err(|e| {
+                anyhow::Error::msg(format!(
+                    "Invalid value within result set. `{}` could not parse `{}`: {}!",
+                    column, value, e
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Parses the columns whose name starts with `prefix`, such as `metric_2020` and
+/// `metric_2021`, into a map for a `#[athena(prefix = "...")]` field.
+///
+/// Each key is the rest of the column name after the prefix, parsed as `K`, so
+/// `metric_2020` gives the key `2020`. A column named exactly `prefix` is ignored, and a
+/// row without any matching column yields an empty map.
+///
+/// # Errors
+///
+/// Returns an error naming the column of the first key or value that could not be parsed.
+pub fn collect_prefixed_map<K, V, M, R>(row: &R, prefix: &str) -> anyhow::Result<M>
+where
+    K: FromAthenaField,
+    V: FromAthenaField,
+    M: FromIterator<(K, V)>,
+    R: RowSource + ?Sized,
+{
+    crate::row::entries(row)
+        .filter_map(|(column, value)| {
+            let key = column.strip_prefix(prefix).filter(|key| !key.is_empty())?;
+            Some((column, key, value))
+        })
+        .map(|(column, key, value)| {
+            let key = K::from_athena_field(key).map_err(|e| {
+                anyhow::Error::msg(format!(
+                    "Invalid key within result set. `{}` could not parse `{}`: {}!",
+                    column, key, e
+                ))
+            })?;
+            let value = V::from_athena_field(value).map_err(|e| {
+                anyhow::Error::msg(format!(
+                    "Invalid value within result set. `{}` could not parse `{}`: {}!",
+                    column, value, e
+                ))
+            })?;
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Collects the columns of a row that are not in `columns`, for a `#[athena(flatten)]` field.
+pub fn flatten<R: RowSource + ?Sized>(row: &R, columns: &[&str]) -> HashMap<String, String> {
+    crate::row::entries(row)
+        .filter(|(column, _)| !columns.contains(column))
+        .map(|(column, value)| (column.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Collects the columns of a row that are not in `columns` as JSON, for a
+/// `#[athena(flatten)]` field of type `serde_json::Map`.
+///
+/// Each value is converted with [`json_value`](crate::json_value) according to its type in
+/// `types`. Columns without a known type are kept as strings.
+#[cfg(feature = "json")]
+pub fn flatten_json<R: RowSource + ?Sized>(
+    row: &R,
+    columns: &[&str],
+    types: &HashMap<String, String>,
+) -> serde_json::Map<String, serde_json::Value> {
+    crate::row::entries(row)
+        .filter(|(column, _)| !columns.contains(column))
+        .map(|(column, value)| {
+            let athena_type = types.get(column).map_or("varchar", String::as_str);
+            (
+                column.to_string(),
+                crate::json_value(athena_type, Some(value)),
+            )
+        })
+        .collect()
+}
+
+fn is_negative_integer(value: &str) -> bool {
+    value
+        .strip_prefix('-')
+        .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Turns negative zero into zero, for a `#[athena(normalize_zero)]` field.
+///
+/// Any other value, including NaN, is returned unchanged.
+pub fn normalize_zero<T: PartialEq + From<i8>>(value: T) -> T {
+    if value == T::from(0) {
+        T::from(0)
+    } else {
+        value
+    }
+}
+
+/// Strips any of the characters in `chars` from both ends of `value`.
+///
+/// Each end is stripped independently, so `"value` becomes `value` just as `"value"` does.
+pub fn trim_matches<'a>(value: &'a str, chars: &str) -> &'a str {
+    value.trim_matches(|c| chars.contains(c))
+}
+
+/// Parses a percentage such as `42%` into an `f64`.
+///
+/// The trailing `%` is optional. When `fraction` is set the value is divided by
+/// 100, so `42%` becomes `0.42` rather than `42.0`.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value is not numeric once the `%` is stripped.
+pub fn parse_percent(field: &str, value: &str, fraction: bool) -> anyhow::Result<f64> {
+    let trimmed = value.trim();
+    let number = trimmed.strip_suffix('%').unwrap_or(trimmed).trim_end();
+
+    let percent = number.parse::<f64>().map_err(|_| {
+        anyhow::Error::msg(format!(
+            "Invalid percentage within result set. `{}` could not parse `{}`!",
+            field, value
+        ))
+    })?;
+
+    if fraction {
+        Ok(percent / 100.0)
+    } else {
+        Ok(percent)
+    }
+}
+
+/// Returns the precision and scale declared by a column type such as `decimal(10,2)`.
+///
+/// `None` is returned for other types, and for a `decimal` without both parameters.
+pub fn decimal_precision_scale(athena_type: &str) -> Option<(u32, u32)> {
+    let params = athena_type
+        .trim()
+        .to_ascii_lowercase()
+        .strip_prefix("decimal")?
+        .trim()
+        .strip_prefix('(')?
+        .strip_suffix(')')?
+        .to_string();
+    let (precision, scale) = params.split_once(',')?;
+
+    Some((precision.trim().parse().ok()?, scale.trim().parse().ok()?))
+}
+
+/// Checks that a decimal value fits a column declared as `decimal(precision, scale)`.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value is not a decimal number, has more than
+/// `scale` fractional digits, or more than `precision - scale` integer digits.
+pub fn validate_decimal(
+    field: &str,
+    value: &str,
+    precision: u32,
+    scale: u32,
+) -> anyhow::Result<()> {
+    let error = |reason: String| {
+        anyhow::Error::msg(format!(
+            "Invalid decimal within result set. `{}` could not parse `{}`: {}!",
+            field, value, reason
+        ))
+    };
+
+    let trimmed = value.trim();
+    let unsigned = trimmed.strip_prefix(['-', '+']).unwrap_or(trimmed);
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+    if integer.is_empty() && fraction.is_empty()
+        || !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return Err(error("not a decimal number".to_string()));
+    }
+
+    if fraction.len() > scale as usize {
+        return Err(error(format!(
+            "overflow, {} fractional digits exceed the scale of {}",
+            fraction.len(),
+            scale
+        )));
+    }
+
+    let integer_digits = integer.trim_start_matches('0').len();
+    let max_integer_digits = precision.saturating_sub(scale) as usize;
+    if integer_digits > max_integer_digits {
+        return Err(error(format!(
+            "overflow, {} integer digits exceed the {} allowed by decimal({},{})",
+            integer_digits, max_integer_digits, precision, scale
+        )));
+    }
+
+    Ok(())
+}
+
+/// Parses a float written with a locale's separators, such as `1.234,5`.
+///
+/// Every `thousands` separator is removed and the `decimal` separator is read as `.`.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value is not a number once the separators are
+/// replaced.
+pub fn parse_localized_float<T: FromStr>(
+    field: &str,
+    value: &str,
+    decimal: char,
+    thousands: Option<char>,
+) -> anyhow::Result<T> {
+    let normalized: String = value
+        .trim()
+        .chars()
+        .filter(|c| Some(*c) != thousands)
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+
+    normalized.parse::<T>().map_err(|_| {
+        anyhow::Error::msg(format!(
+            "Invalid float within result set. `{}` could not parse `{}`!",
+            field, value
+        ))
+    })
+}
+
+/// Parses a `char`, such as the value of a `char(1)` column, which may be any Unicode
+/// scalar value including multi-byte ones such as `€`.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value is empty or holds more than one character.
+pub fn parse_char(field: &str, value: &str) -> anyhow::Result<char> {
+    let mut chars = value.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        (None, _) => Err(anyhow::Error::msg(format!(
+            "Invalid character within result set. `{}` is empty!",
+            field
+        ))),
+        (Some(_), Some(_)) => Err(anyhow::Error::msg(format!(
+            "Invalid character within result set. `{}` could not parse `{}`, expected a single character!",
+            field, value
+        ))),
+    }
+}
+
+/// Parses a `bool` stored as an integer, where `0` is `false` and `1` is `true`.
+///
+/// # Errors
+///
+/// Returns an error naming `field` for any value other than `0` or `1`.
+pub fn parse_bool_from_int(field: &str, value: &str) -> anyhow::Result<bool> {
+    match value.trim() {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(anyhow::Error::msg(format!(
+            "Invalid boolean within result set. `{}` could not parse `{}`, expected `0` or `1`!",
+            field, value
+        ))),
+    }
+}
+
+/// Parses a UUID such as `67e55044-10b1-426f-9247-bb680e5fe0c8` into a `uuid::Uuid`.
+///
+/// # Errors
+///
+/// Returns an error naming `field` and the malformed value.
+#[cfg(feature = "uuid")]
+pub fn parse_uuid(field: &str, value: &str) -> anyhow::Result<uuid::Uuid> {
+    uuid::Uuid::parse_str(value.trim()).map_err(|e| {
+        anyhow::Error::msg(format!(
+            "Invalid UUID within result set. `{}` could not parse `{}`: {}",
+            field, value, e
+        ))
+    })
+}
+
+/// Parses an absolute URL such as `https://example.com/path` into a `url::Url`.
+///
+/// # Errors
+///
+/// Returns an error naming `field` and the value if it is not an absolute URL.
+#[cfg(feature = "url")]
+pub fn parse_url(field: &str, value: &str) -> anyhow::Result<url::Url> {
+    url::Url::parse(value.trim()).map_err(|e| {
+        anyhow::Error::msg(format!(
+            "Invalid URL within result set. `{}` could not parse `{}`: {}",
+            field, value, e
+        ))
+    })
+}
+
+/// Parses a `decimal` or `double` value such as `12345678901234567890.0123456789` into a
+/// `bigdecimal::BigDecimal`, keeping every digit.
+///
+/// # Errors
+///
+/// Returns an error naming `field` and the value if it is not a decimal number.
+#[cfg(feature = "bigdecimal")]
+pub fn parse_big_decimal(field: &str, value: &str) -> anyhow::Result<bigdecimal::BigDecimal> {
+    value.trim().parse().map_err(|e| {
+        anyhow::Error::msg(format!(
+            "Invalid decimal within result set. `{}` could not parse `{}`: {}",
+            field, value, e
+        ))
+    })
+}
+
+/// Parses an Athena `timestamp` into a `std::time::SystemTime`.
+///
+/// A value made only of digits, optionally signed, is read as milliseconds since the UNIX
+/// epoch. Anything else is read as Athena's timestamp format, `2024-01-31 12:30:00.123`,
+/// where a `T` may also separate the date and time and the fractional seconds are optional.
+/// Athena timestamps carry no time zone, so they are interpreted as UTC.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value is in neither format.
+#[cfg(feature = "system-time")]
+pub fn parse_system_time(field: &str, value: &str) -> anyhow::Result<std::time::SystemTime> {
+    let trimmed = value.trim();
+
+    let millis = match trimmed.parse::<i64>() {
+        Ok(millis) => Some(millis as i128),
+        Err(_) => timestamp_millis(trimmed),
+    };
+
+    millis
+        .and_then(|millis| {
+            let offset = std::time::Duration::from_millis(millis.unsigned_abs() as u64);
+            if millis < 0 {
+                std::time::UNIX_EPOCH.checked_sub(offset)
+            } else {
+                std::time::UNIX_EPOCH.checked_add(offset)
+            }
+        })
+        .ok_or_else(|| {
+            anyhow::Error::msg(format!(
+                "Invalid timestamp within result set. `{}` could not parse `{}`!",
+                field, value
+            ))
+        })
+}
+
+/// Returns the milliseconds since the UNIX epoch of a UTC `YYYY-MM-DD HH:MM:SS[.fff]` timestamp.
+///
+/// The year must be between 0 and 9999, and the day must exist in its month.
+#[cfg(feature = "system-time")]
+fn timestamp_millis(value: &str) -> Option<i128> {
+    let (date, time) = value.split_once([' ', 'T'])?;
+
+    let mut date = date.splitn(3, '-');
+    let year = unsigned(date.next()?)?;
+    let month = unsigned(date.next()?)?;
+    let day = unsigned(date.next()?)?;
+
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.splitn(3, ':');
+    let hour = unsigned(time.next()?)?;
+    let minute = unsigned(time.next()?)?;
+    let second = unsigned(time.next()?)?;
+
+    if year > 9999
+        || !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    // Only the first three digits of the fraction fit within milliseconds.
+    let millis = format!("{:0<3}", &fraction[..fraction.len().min(3)])
+        .parse::<i64>()
+        .ok()?;
+
+    // Days since the epoch of the civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let (year, month, day) = (i64::from(year), i64::from(month), i64::from(day));
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    let seconds = days
+        .checked_mul(86400)?
+        .checked_add(i64::from(hour * 3600 + minute * 60 + second))?;
+    i128::from(seconds)
+        .checked_mul(1000)?
+        .checked_add(i128::from(millis))
+}
+
+/// Parses a component of a timestamp made only of ASCII digits, without a sign.
+#[cfg(feature = "system-time")]
+fn unsigned(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// Returns the number of days in a month of the proleptic Gregorian calendar.
+#[cfg(feature = "system-time")]
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parses an integer UNIX timestamp into a `chrono::DateTime<Utc>`.
+///
+/// When `millis` is set the value is read as milliseconds since the epoch, otherwise
+/// as seconds.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value is not an integer, or is outside the
+/// range `chrono` can represent.
+#[cfg(feature = "chrono")]
+pub fn parse_epoch(
+    field: &str,
+    value: &str,
+    millis: bool,
+) -> anyhow::Result<chrono::DateTime<chrono::Utc>> {
+    let timestamp = value.trim().parse::<i64>().map_err(|_| {
+        anyhow::Error::msg(format!(
+            "Invalid epoch within result set. `{}` could not parse `{}`!",
+            field, value
+        ))
+    })?;
+
+    let date_time = if millis {
+        chrono::DateTime::from_timestamp_millis(timestamp)
+    } else {
+        chrono::DateTime::from_timestamp(timestamp, 0)
+    };
+
+    date_time.ok_or_else(|| {
+        anyhow::Error::msg(format!(
+            "Invalid epoch within result set. `{}` is out of range: `{}`!",
+            field, value
+        ))
+    })
+}
+
+/// Checks a parsed value against the inclusive bounds of a `#[athena(min = .., max = ..)]`
+/// field, returning it unchanged when it is within them.
+///
+/// # Errors
+///
+/// Returns an error naming `field`, the value and the bound it violates.
+pub fn check_range<T: PartialOrd + std::fmt::Display>(
+    field: &str,
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+) -> anyhow::Result<T> {
+    if let Some(min) = min.filter(|min| value < *min) {
+        return Err(anyhow::Error::msg(format!(
+            "Out of range value within result set. `{}` is `{}`, below the minimum of `{}`!",
+            field, value, min
+        )));
+    }
+    if let Some(max) = max.filter(|max| value > *max) {
+        return Err(anyhow::Error::msg(format!(
+            "Out of range value within result set. `{}` is `{}`, above the maximum of `{}`!",
+            field, value, max
+        )));
+    }
+
+    Ok(value)
+}
+
+/// Parses a non-negative number of seconds, such as `90` or `1.5`, into a
+/// `std::time::Duration`.
+///
+/// When `millis` is set the value is read as milliseconds instead. Whole numbers are
+/// converted exactly, while fractional values are rounded to the nanosecond.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value is not a number, is negative, or is too
+/// large for a `Duration`.
+pub fn parse_duration(
+    field: &str,
+    value: &str,
+    millis: bool,
+) -> anyhow::Result<std::time::Duration> {
+    let trimmed = value.trim();
+
+    if let Ok(whole) = trimmed.parse::<u64>() {
+        return Ok(if millis {
+            std::time::Duration::from_millis(whole)
+        } else {
+            std::time::Duration::from_secs(whole)
+        });
+    }
+
+    let number = trimmed.parse::<f64>().map_err(|_| {
+        anyhow::Error::msg(format!(
+            "Invalid duration within result set. `{}` could not parse `{}`!",
+            field, value
+        ))
+    })?;
+
+    if number < 0.0 {
+        return Err(anyhow::Error::msg(format!(
+            "Invalid duration within result set. `{}` is negative: `{}`!",
+            field, value
+        )));
+    }
+
+    let seconds = if millis { number / 1000.0 } else { number };
+    std::time::Duration::try_from_secs_f64(seconds).map_err(|_| {
+        anyhow::Error::msg(format!(
+            "Invalid duration within result set. `{}` is out of range: `{}`!",
+            field, value
+        ))
+    })
+}
+
+/// A `chrono` type parsed with a format string, for [`parse_with_formats`].
+#[cfg(feature = "chrono")]
+pub trait FromFormat: Sized {
+    /// Parses `value` according to `format`, as `chrono`'s `parse_from_str` does.
+    fn from_format(value: &str, format: &str) -> chrono::ParseResult<Self>;
+}
+
+#[cfg(feature = "chrono")]
+impl FromFormat for chrono::NaiveDate {
+    fn from_format(value: &str, format: &str) -> chrono::ParseResult<Self> {
+        Self::parse_from_str(value, format)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl FromFormat for chrono::NaiveDateTime {
+    fn from_format(value: &str, format: &str) -> chrono::ParseResult<Self> {
+        Self::parse_from_str(value, format)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl FromFormat for chrono::NaiveTime {
+    fn from_format(value: &str, format: &str) -> chrono::ParseResult<Self> {
+        Self::parse_from_str(value, format)
+    }
+}
+
+/// Parses a date or time with each of `formats` in turn, returning the first success.
+///
+/// # Errors
+///
+/// Returns an error naming `field` and every format tried if none of them parse the value.
+#[cfg(feature = "chrono")]
+pub fn parse_with_formats<T: FromFormat>(
+    field: &str,
+    value: &str,
+    formats: &[&str],
+) -> anyhow::Result<T> {
+    let trimmed = value.trim();
+
+    formats
+        .iter()
+        .find_map(|format| T::from_format(trimmed, format).ok())
+        .ok_or_else(|| {
+            anyhow::Error::msg(format!(
+                "Invalid date within result set. `{}` could not parse `{}` with any of `{}`!",
+                field,
+                value,
+                formats.join("`, `")
+            ))
+        })
+}
+
+/// Parses a WKT geometry such as `POINT (1 2)`, as returned by Athena's geospatial
+/// functions, into a `geo_types::Geometry`.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value is not valid WKT.
+#[cfg(feature = "geo")]
+pub fn parse_wkt(field: &str, value: &str) -> anyhow::Result<geo_types::Geometry<f64>> {
+    use wkt::TryFromWkt;
+
+    geo_types::Geometry::try_from_wkt_str(value.trim()).map_err(|e| {
+        anyhow::Error::msg(format!(
+            "Invalid WKT within result set. `{}` could not parse `{}`: {}",
+            field, value, e
+        ))
+    })
+}
+
+/// Parses an Athena `timestamp with time zone` carrying an IANA zone name, such as
+/// `2024-01-31 12:30:00.000 America/New_York`, into a `chrono::DateTime<chrono_tz::Tz>`.
+///
+/// Local times that occur twice because clocks go back at the end of daylight saving time
+/// resolve to the earliest of the two instants. Local times skipped when clocks go
+/// forward do not exist in the zone and are rejected.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the timestamp or the zone name is malformed, or the
+/// local time does not exist in the zone.
+#[cfg(feature = "chrono-tz")]
+pub fn parse_zoned(field: &str, value: &str) -> anyhow::Result<chrono::DateTime<chrono_tz::Tz>> {
+    use chrono::TimeZone;
+
+    let error = |reason: &str| {
+        anyhow::Error::msg(format!(
+            "Invalid zoned timestamp within result set. `{}` could not parse `{}`: {}!",
+            field, value, reason
+        ))
+    };
+
+    let (local, zone) = value
+        .trim()
+        .rsplit_once(' ')
+        .ok_or_else(|| error("expected a timestamp followed by a zone name"))?;
+    let zone = zone
+        .parse::<chrono_tz::Tz>()
+        .map_err(|_| error("unknown zone name"))?;
+    let local = chrono::NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M:%S%.f")
+        .map_err(|_| error("malformed timestamp"))?;
+
+    zone.from_local_datetime(&local)
+        .earliest()
+        .ok_or_else(|| error("the local time does not exist in the zone"))
+}
+
+/// Splits an Athena array such as `[a, b, c]`, or a plain list such as `a,b,c`, into its elements.
+///
+/// Surrounding brackets are optional and whitespace around each element is trimmed.
+/// An empty array yields no elements.
+pub fn split_array(value: &str) -> Vec<&str> {
+    split_array_with(value, ",")
+}
+
+/// Splits an array as [`split_array`] does, with elements separated by `delimiter`.
+pub fn split_array_with<'a>(value: &'a str, delimiter: &str) -> Vec<&'a str> {
+    let trimmed = value.trim();
+    let inner = trimmed
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .unwrap_or(trimmed)
+        .trim();
+
+    if inner.is_empty() {
+        return vec![];
+    }
+
+    inner.split(delimiter).map(str::trim).collect()
+}
+
+/// Splits a delimited value such as `12,34` into the elements of a tuple with `arity` elements.
+///
+/// Whitespace around each element is trimmed.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the number of elements is not `arity`.
+pub fn split_tuple<'a>(
+    field: &str,
+    value: &'a str,
+    delimiter: &str,
+    arity: usize,
+) -> anyhow::Result<Vec<&'a str>> {
+    let parts: Vec<&str> = value.split(delimiter).map(str::trim).collect();
+
+    if parts.len() != arity {
+        return Err(anyhow::Error::msg(format!(
+            "Invalid tuple within result set. `{}` expected {} elements but `{}` has {}!",
+            field,
+            arity,
+            value,
+            parts.len()
+        )));
+    }
+
+    Ok(parts)
+}
+
+/// Returns part `index` of `count` parts of a delimited value, for a group of
+/// `#[athena(flatten_delimited)]` fields reading one column.
+///
+/// The value is split on `delimiter` into at most `count` parts, so the last part keeps any
+/// further delimiters. Whitespace around each part is trimmed.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value has fewer than `count` parts.
+pub fn split_part<'a>(
+    field: &str,
+    value: &'a str,
+    delimiter: &str,
+    index: usize,
+    count: usize,
+) -> anyhow::Result<&'a str> {
+    let parts: Vec<&str> = value.splitn(count, delimiter).map(str::trim).collect();
+
+    if parts.len() < count {
+        return Err(anyhow::Error::msg(format!(
+            "Invalid delimited value within result set. `{}` expected {} parts but `{}` has {}!",
+            field,
+            count,
+            value,
+            parts.len()
+        )));
+    }
+
+    Ok(parts[index])
+}
+
+/// Parses an Athena array into any collection of `T`, such as `Vec<T>`, `HashSet<T>` or `BTreeSet<T>`.
+///
+/// Each element from [`split_array`] is parsed with [`FromAthenaField`], that is `str::parse`. Collecting into a set
+/// silently merges duplicate elements.
+///
+/// # Errors
+///
+/// Returns the error of the first element that fails to parse.
+pub fn parse_array<T, C>(value: &str) -> anyhow::Result<C>
+where
+    T: FromAthenaField,
+    C: FromIterator<T>,
+{
+    parse_array_with(value, ",")
+}
+
+/// Parses an array as [`parse_array`] does, with elements separated by `delimiter`.
+///
+/// # Errors
+///
+/// Returns the error of the first element that fails to parse.
+pub fn parse_array_with<T, C>(value: &str, delimiter: &str) -> anyhow::Result<C>
+where
+    T: FromAthenaField,
+    C: FromIterator<T>,
+{
+    split_array_with(value, delimiter)
+        .into_iter()
+        .map(|elem| {
+            T::from_athena_field(elem).map_err(|e| match elem {
+                "null" => anyhow::Error::msg(
+                    "Invalid array within result set. A `null` element can only be read into an `Option` element!",
+                ),
+                _ => e,
+            })
+        })
+        .collect()
+}
+
+/// Parses an array whose elements may be null, such as `[1, null, 3]`, into any
+/// collection of `Option<T>`.
+///
+/// Elements equal to `null_token` become `None`, every other element is parsed as
+/// [`parse_array_with`] does.
+///
+/// # Errors
+///
+/// Returns the error of the first element that fails to parse.
+pub fn parse_optional_array_with<T, C>(
+    value: &str,
+    delimiter: &str,
+    null_token: &str,
+) -> anyhow::Result<C>
+where
+    T: FromAthenaField,
+    C: FromIterator<Option<T>>,
+{
+    split_array_with(value, delimiter)
+        .into_iter()
+        .map(|elem| {
+            if elem == null_token {
+                Ok(None)
+            } else {
+                Ok(Some(T::from_athena_field(elem)?))
+            }
+        })
+        .collect()
+}
+
+/// Parses an Athena map such as `{a=1, b=2}` into any map of `K` to `V`, such as
+/// `HashMap<K, V>` or `BTreeMap<K, V>`.
+///
+/// Surrounding braces are optional. Entries are separated by `entry_delimiter` and each
+/// key is separated from its value by the first `kv_delimiter`. Whitespace around keys
+/// and values is trimmed, and an empty map yields no entries.
+///
+/// # Errors
+///
+/// Returns an error naming `field` for an entry without `kv_delimiter`, or the error of
+/// the first key or value that fails to parse.
+pub fn parse_map<K, V, M>(
+    field: &str,
+    value: &str,
+    entry_delimiter: &str,
+    kv_delimiter: &str,
+) -> anyhow::Result<M>
+where
+    K: FromAthenaField,
+    V: FromAthenaField,
+    M: FromIterator<(K, V)>,
+{
+    let trimmed = value.trim();
+    let inner = trimmed
+        .strip_prefix('{')
+        .and_then(|v| v.strip_suffix('}'))
+        .unwrap_or(trimmed)
+        .trim();
+
+    if inner.is_empty() {
+        return Ok(std::iter::empty().collect());
+    }
+
+    inner
+        .split(entry_delimiter)
+        .map(|entry| {
+            let (key, value) = entry.split_once(kv_delimiter).ok_or_else(|| {
+                anyhow::Error::msg(format!(
+                    "Invalid map within result set. `{}` has an entry without `{}`: `{}`!",
+                    field,
+                    kv_delimiter,
+                    entry.trim()
+                ))
+            })?;
+            Ok((
+                K::from_athena_field(key.trim())?,
+                V::from_athena_field(value.trim())?,
+            ))
+        })
+        .collect()
+}
+
+/// Parses an Athena `varbinary` value into a `Vec<u8>`, see [`decode_binary_into`].
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value is not valid hex.
+pub fn parse_binary(field: &str, value: &str) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len() / 2);
+
+    decode_binary_into(value, &mut bytes).map_err(|e| {
+        anyhow::Error::msg(format!(
+            "Invalid binary within result set. `{}` could not be decoded: {}",
+            field, e
+        ))
+    })?;
+
+    Ok(bytes)
+}
+
+/// Parses a hex string such as `deadbeef` into a `Vec<u8>`.
+///
+/// Whitespace anywhere in the value is ignored, while [`parse_binary`] expects Athena's
+/// whole hex pairs.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value has an odd number of digits or a
+/// character that is not a hex digit.
+pub fn parse_hex(field: &str, value: &str) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len() / 2);
+
+    decode_hex_into(value.trim(), &mut bytes).map_err(|e| {
+        anyhow::Error::msg(format!(
+            "Invalid hex within result set. `{}` could not be decoded: {}",
+            field, e
+        ))
+    })?;
+
+    Ok(bytes)
+}
+
+/// Parses a base64 string such as `3q2+7w==` into a `Vec<u8>`, for a
+/// `#[athena(bytes_encoding = "base64")]` field.
+///
+/// Both the standard and URL safe alphabets are accepted, whitespace is ignored and the
+/// trailing `=` padding is optional.
+///
+/// # Errors
+///
+/// Returns an error naming `field` if the value has a character outside of the base64
+/// alphabets, an invalid length, anything after the padding or the wrong amount of padding.
+pub fn parse_base64(field: &str, value: &str) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len() / 4 * 3);
+
+    decode_base64_into(value, &mut bytes).map_err(|e| {
+        anyhow::Error::msg(format!(
+            "Invalid base64 within result set. `{}` could not be decoded: {}",
+            field, e
+        ))
+    })?;
+
+    Ok(bytes)
+}
+
+/// Decodes a binary value, appending the bytes to `out`.
+///
+/// Athena renders `varbinary` as space separated hex pairs (`68 65 6c 6c 6f`), which may
+/// also be written without the spaces (`68656c6c6f`). The value is always decoded as hex,
+/// never guessed to be base64, since a base64 value such as `AAAA` is also valid hex. Use
+/// `#[athena(bytes_encoding = "base64")]` for base64 values.
+///
+/// The value is decoded a few characters at a time straight into `out`, so no intermediate
+/// copy of the value is made. Reusing `out` across calls also reuses its allocation.
+///
+/// # Errors
+///
+/// Returns an error if the value is not hex, or a group of digits between spaces is not a
+/// whole number of pairs. On error, `out` may hold the bytes decoded before the invalid
+/// character.
+pub fn decode_binary_into(value: &str, out: &mut Vec<u8>) -> anyhow::Result<()> {
+    if value
+        .split_ascii_whitespace()
+        .any(|pair| pair.len() % 2 != 0)
+    {
+        return Err(anyhow::Error::msg(
+            "hex value has a group with an odd number of digits",
+        ));
+    }
+
+    decode_hex_into(value, out)
+}
+
+fn decode_hex_into(value: &str, out: &mut Vec<u8>) -> anyhow::Result<()> {
+    let mut high: Option<u8> = None;
+
+    for b in value.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        let nibble = hex_value(b)
+            .ok_or_else(|| anyhow::Error::msg(format!("`{}` is not a hex digit", b as char)))?;
+
+        match high.take() {
+            Some(h) => out.push(h << 4 | nibble),
+            None => high = Some(nibble),
+        }
+    }
+
+    match high {
+        Some(_) => Err(anyhow::Error::msg("hex value has an odd number of digits")),
+        None => Ok(()),
+    }
+}
+
+fn hex_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn decode_base64_into(value: &str, out: &mut Vec<u8>) -> anyhow::Result<()> {
+    let mut chunk = [0u8; 4];
+    let mut len = 0;
+    let mut padding = 0;
+
+    for b in value.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        if b == b'=' {
+            padding += 1;
+            continue;
+        }
+        if padding > 0 {
+            return Err(anyhow::Error::msg(
+                "base64 value has data after its padding",
+            ));
+        }
+
+        chunk[len] = base64_value(b).ok_or_else(|| {
+            anyhow::Error::msg(format!("`{}` is not a base64 character", b as char))
+        })?;
+        len += 1;
+
+        if len == 4 {
+            out.extend_from_slice(&[
+                chunk[0] << 2 | chunk[1] >> 4,
+                chunk[1] << 4 | chunk[2] >> 2,
+                chunk[2] << 6 | chunk[3],
+            ]);
+            len = 0;
+        }
+    }
+
+    // Padding, when present, must complete the last group of four characters.
+    if padding > 0 && (len == 0 || len + padding != 4) {
+        return Err(anyhow::Error::msg("base64 value has invalid padding"));
+    }
+
+    match len {
+        0 => {}
+        2 => out.push(chunk[0] << 2 | chunk[1] >> 4),
+        3 => out.extend_from_slice(&[chunk[0] << 2 | chunk[1] >> 4, chunk[1] << 4 | chunk[2] >> 2]),
+        _ => return Err(anyhow::Error::msg("base64 value has an invalid length")),
+    }
+
+    Ok(())
+}
+
+fn base64_value(b: u8) -> Option<u8> {
+    match b {
+        b'A'..=b'Z' => Some(b - b'A'),
+        b'a'..=b'z' => Some(b - b'a' + 26),
+        b'0'..=b'9' => Some(b - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+/// Renders array elements as Athena does, such as `[a, b, c]`, for [`parse_array_with`].
+pub fn format_array(elements: impl IntoIterator<Item = String>, delimiter: &str) -> String {
+    let elements: Vec<String> = elements.into_iter().collect();
+    format!("[{}]", elements.join(delimiter))
+}
+
+/// Renders map entries as Athena does, such as `{a=1, b=2}`, for [`parse_map`].
+pub fn format_map(
+    entries: impl IntoIterator<Item = (String, String)>,
+    entry_delimiter: &str,
+    kv_delimiter: &str,
+) -> String {
+    let entries: Vec<String> = entries
+        .into_iter()
+        .map(|(key, value)| format!("{}{}{}", key, kv_delimiter, value))
+        .collect();
+    format!("{{{}}}", entries.join(entry_delimiter))
+}
+
+/// Renders an `f64` as a percentage such as `98.5%`, for [`parse_percent`].
+///
+/// When `fraction` is set the value is multiplied by 100 by moving the decimal point of
+/// its shortest representation, so `0.985` becomes `98.5%` rather than picking up the
+/// rounding error of `0.985 * 100.0`.
+pub fn format_percent(value: f64, fraction: bool) -> String {
+    if !fraction || !value.is_finite() {
+        return format!("{}%", if fraction { value * 100.0 } else { value });
+    }
+
+    let text = value.to_string();
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", text.as_str()),
+    };
+    let (whole, decimals) = digits.split_once('.').unwrap_or((digits, ""));
+    let decimals = format!("{:0<2}", decimals);
+    let (shifted, rest) = decimals.split_at(2);
+
+    let whole = format!("{}{}", whole, shifted);
+    let whole = match whole.trim_start_matches('0') {
+        "" => "0",
+        trimmed => trimmed,
+    };
+
+    if rest.is_empty() {
+        format!("{}{}%", sign, whole)
+    } else {
+        format!("{}{}.{}%", sign, whole, rest)
+    }
+}
+
+/// Renders bytes as a lowercase hex string such as `deadbeef`, for [`parse_hex`] and
+/// [`parse_binary`].
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Renders bytes as padded standard base64 such as `3q2+7w==`, for [`parse_base64`].
+pub fn format_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Renders a `std::time::SystemTime` as milliseconds since the UNIX epoch, for
+/// [`parse_system_time`].
+#[cfg(feature = "system-time")]
+pub fn format_system_time(time: &std::time::SystemTime) -> String {
+    let millis = match time.duration_since(std::time::UNIX_EPOCH) {
+        Ok(after) => after.as_millis() as i128,
+        Err(before) => -(before.duration().as_millis() as i128),
+    };
+    millis.to_string()
+}
+
+/// Renders a `chrono::DateTime<chrono_tz::Tz>` as a timestamp followed by its IANA zone
+/// name, such as `2024-01-31 12:30:00.000 America/New_York`, for [`parse_zoned`].
+#[cfg(feature = "chrono-tz")]
+pub fn format_zoned(date_time: &chrono::DateTime<chrono_tz::Tz>) -> String {
+    format!(
+        "{} {}",
+        date_time.naive_local().format("%Y-%m-%d %H:%M:%S%.3f"),
+        date_time.timezone().name()
+    )
+}
+
+/// Renders a `geo_types::Geometry` as WKT such as `POINT(1 2)`, for [`parse_wkt`].
+#[cfg(feature = "geo")]
+pub fn format_wkt(geometry: &geo_types::Geometry<f64>) -> String {
+    use wkt::ToWkt;
+
+    geometry.wkt_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_medium_binary() {
+        let blob: Vec<u8> = (0..4096u32).map(|i| (i * 31 % 256) as u8).collect();
+
+        let hex = blob
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut out = Vec::new();
+        decode_binary_into(&hex, &mut out).unwrap();
+        assert_eq!(out, blob);
+
+        let base64 = format_base64(&blob);
+        out.clear();
+        decode_base64_into(&base64, &mut out).unwrap();
+        assert_eq!(out, blob);
+    }
+
+    #[test]
+    fn decode_small_binary() {
+        assert_eq!(parse_binary("data", "68 65 6c 6c 6f").unwrap(), b"hello");
+        assert_eq!(parse_binary("data", "68656c6c6f").unwrap(), b"hello");
+        assert_eq!(parse_binary("data", "").unwrap(), b"");
+        assert!(parse_binary("data", "aGVsbG8=").is_err());
+        assert!(parse_binary("data", "686 56c").is_err());
+
+        // Also valid base64 for three zero bytes, but binary values are always hex.
+        assert_eq!(parse_binary("data", "AAAA").unwrap(), vec![0xaa, 0xaa]);
+        assert_eq!(parse_base64("data", "AAAA").unwrap(), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn decode_base64_padding() {
+        assert_eq!(parse_base64("data", "aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(parse_base64("data", "aGVsbG8").unwrap(), b"hello");
+        assert_eq!(parse_base64("data", "aGVsbA==").unwrap(), b"hell");
+
+        for invalid in ["AB==garbage", "aGVsbG8==", "aGVsbA=", "====", "AB=C"] {
+            assert!(parse_base64("data", invalid).is_err(), "{}", invalid);
+        }
+        assert_eq!(
+            parse_base64("data", "AB==garbage").unwrap_err().to_string(),
+            "Invalid base64 within result set. `data` could not be decoded: base64 value has data after its padding"
+        );
+    }
+
+    #[cfg(feature = "system-time")]
+    #[test]
+    fn parse_system_time_values() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let expected = UNIX_EPOCH + Duration::from_millis(1_706_704_200_123);
+        assert_eq!(
+            parse_system_time("created", "2024-01-31 12:30:00.123").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_system_time("created", "2024-01-31T12:30:00.123456").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_system_time("created", "1706704200123").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_system_time("created", "1969-12-31 23:59:59").unwrap(),
+            UNIX_EPOCH - Duration::from_secs(1)
+        );
+        assert_eq!(
+            parse_system_time("created", "yesterday")
+                .unwrap_err()
+                .to_string(),
+            "Invalid timestamp within result set. `created` could not parse `yesterday`!"
+        );
+    }
+
+    #[cfg(feature = "system-time")]
+    #[test]
+    fn parse_system_time_rejects_invalid_dates() {
+        assert!(parse_system_time("created", "2024-02-29 00:00:00").is_ok());
+        assert!(parse_system_time("created", "2000-02-29 00:00:00").is_ok());
+
+        for value in [
+            "2023-02-29 00:00:00",
+            "1900-02-29 00:00:00",
+            "2024-04-31 00:00:00",
+            "2024-01-31 +1:00:00",
+            "2024-01-31 12:-0:00",
+            "10000-01-01 00:00:00",
+            "99999999999999-01-01 00:00:00",
+        ] {
+            assert!(parse_system_time("created", value).is_err(), "{}", value);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn parse_epoch_values() {
+        let expected = "2024-01-31T12:30:00Z"
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .unwrap();
+
+        assert_eq!(
+            parse_epoch("created", "1706704200", false).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_epoch("created", "1706704200000", true).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_epoch("created", "9223372036854775807", false)
+                .unwrap_err()
+                .to_string(),
+            "Invalid epoch within result set. `created` is out of range: `9223372036854775807`!"
+        );
+        assert_eq!(
+            parse_epoch("created", "soon", true)
+                .unwrap_err()
+                .to_string(),
+            "Invalid epoch within result set. `created` could not parse `soon`!"
+        );
+    }
+
+    #[cfg(feature = "geo")]
+    #[test]
+    fn parse_wkt_values() {
+        assert_eq!(
+            parse_wkt("location", "POINT (1 2)").unwrap(),
+            geo_types::Geometry::Point(geo_types::Point::new(1.0, 2.0))
+        );
+        assert!(parse_wkt("location", "POINT (1)")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid WKT within result set. `location` could not parse `POINT (1)`"));
+    }
+
+    #[test]
+    fn parse_int_errors() {
+        assert_eq!(parse_int::<i64>("test", "-42").unwrap(), -42);
+        assert_eq!(
+            parse_int::<i64>("test", "99999999999999999999")
+                .unwrap_err()
+                .to_string(),
+            "Invalid integer within result set. `test` could not parse `99999999999999999999`: overflow, the value is too large for the field type!"
+        );
+        assert_eq!(
+            parse_int::<u8>("test", "-1").unwrap_err().to_string(),
+            "Invalid integer within result set. `test` could not parse `-1`: overflow, negative values do not fit the unsigned field type!"
+        );
+        assert_eq!(
+            parse_int::<u8>("test", "-x").unwrap_err().to_string(),
+            "Invalid integer within result set. `test` could not parse `-x`: invalid digit!"
+        );
+        assert_eq!(
+            parse_int::<i8>("test", "-129").unwrap_err().to_string(),
+            "Invalid integer within result set. `test` could not parse `-129`: overflow, the value is too small for the field type!"
+        );
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn parse_zoned_values() {
+        let utc = |value: &str| {
+            parse_zoned("created", value)
+                .unwrap()
+                .naive_utc()
+                .to_string()
+        };
+
+        assert_eq!(
+            utc("2024-01-31 12:30:00.000 America/New_York"),
+            "2024-01-31 17:30:00"
+        );
+        assert_eq!(
+            parse_zoned("created", "2024-01-31 12:30:00 Asia/Kolkata")
+                .unwrap()
+                .to_rfc3339(),
+            "2024-01-31T12:30:00+05:30"
+        );
+
+        // 01:30 occurs twice when daylight saving time ends, the earliest is in EDT.
+        assert_eq!(
+            utc("2024-11-03 01:30:00.000 America/New_York"),
+            "2024-11-03 05:30:00"
+        );
+
+        // 02:30 is skipped when daylight saving time starts.
+        assert_eq!(
+            parse_zoned("created", "2024-03-10 02:30:00.000 America/New_York")
+                .unwrap_err()
+                .to_string(),
+            "Invalid zoned timestamp within result set. `created` could not parse `2024-03-10 02:30:00.000 America/New_York`: the local time does not exist in the zone!"
+        );
+        assert!(parse_zoned("created", "2024-01-31 12:30:00.000 Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn parse_map_values() {
+        let res: HashMap<String, i64> = parse_map("counts", "{a=1, b=2}", ",", "=").unwrap();
+        assert_eq!(
+            res,
+            HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+        );
+
+        let res: HashMap<String, i64> = parse_map("counts", "{}", ",", "=").unwrap();
+        assert!(res.is_empty());
+
+        let res: anyhow::Result<HashMap<String, i64>> = parse_map("counts", "{a=1, b}", ",", "=");
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid map within result set. `counts` has an entry without `=`: `b`!"
+        );
+    }
+
+    #[test]
+    fn format_values_round_trip() {
+        let array = format_array(["1".to_string(), "2".to_string()], ", ");
+        assert_eq!(array, "[1, 2]");
+        assert_eq!(parse_array::<i64, Vec<_>>(&array).unwrap(), vec![1, 2]);
+
+        let map = format_map([("a".to_string(), "1".to_string())], ", ", "=");
+        assert_eq!(map, "{a=1}");
+
+        let hex = format_hex(&[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(hex, "deadbeef");
+        assert_eq!(
+            parse_hex("bytes", &hex).unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn parse_big_decimal_values() {
+        let value = "12345678901234567890.0123456789012345678901";
+        let decimal = parse_big_decimal("amount", value).unwrap();
+        assert_eq!(decimal.to_string(), value);
+        assert_eq!(
+            parse_big_decimal("amount", " 1e-30 ").unwrap().to_string(),
+            "1E-30"
+        );
+
+        assert!(parse_big_decimal("amount", "12.5.1")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid decimal within result set. `amount` could not parse `12.5.1`"));
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn parse_url_values() {
+        let url = parse_url("link", "https://example.com/a?b=c").unwrap();
+        assert_eq!(url.host_str(), Some("example.com"));
+
+        assert_eq!(
+            parse_url("link", "/relative/path").unwrap_err().to_string(),
+            "Invalid URL within result set. `link` could not parse `/relative/path`: relative URL without a base"
+        );
+    }
+
+    #[test]
+    fn validate_decimal_values() {
+        assert_eq!(decimal_precision_scale("decimal(10,2)"), Some((10, 2)));
+        assert_eq!(decimal_precision_scale("DECIMAL(38, 0)"), Some((38, 0)));
+        assert_eq!(decimal_precision_scale("double"), None);
+
+        assert!(validate_decimal("price", "-12345678.90", 10, 2).is_ok());
+        assert_eq!(
+            validate_decimal("price", "1.234", 10, 2)
+                .unwrap_err()
+                .to_string(),
+            "Invalid decimal within result set. `price` could not parse `1.234`: overflow, 3 fractional digits exceed the scale of 2!"
+        );
+        assert!(validate_decimal("price", "123456789", 10, 2).is_err());
+        assert!(validate_decimal("price", "1.2.3", 10, 2).is_err());
+    }
+
+    #[test]
+    fn parse_arrays_with_nulls() {
+        let res: Vec<Option<i64>> = parse_optional_array_with("[1, null, 3]", ",", "null").unwrap();
+        assert_eq!(res, vec![Some(1), None, Some(3)]);
+
+        let res: Vec<Option<i64>> = parse_optional_array_with("[1, NA]", ",", "NA").unwrap();
+        assert_eq!(res, vec![Some(1), None]);
+
+        let res: anyhow::Result<Vec<i64>> = parse_array("[1, null, 3]");
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid array within result set. A `null` element can only be read into an `Option` element!"
+        );
+    }
+
+    #[test]
+    fn parse_hex_values() {
+        assert_eq!(
+            parse_hex("data", "deadbeef").unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(
+            parse_hex("data", "abc").unwrap_err().to_string(),
+            "Invalid hex within result set. `data` could not be decoded: hex value has an odd number of digits"
+        );
+        assert_eq!(
+            parse_hex("data", "deadbeeg").unwrap_err().to_string(),
+            "Invalid hex within result set. `data` could not be decoded: `g` is not a hex digit"
+        );
+    }
+}
@@ -0,0 +1,411 @@
+use crate::{FromAthena, HashMap};
+use aws_sdk_athena::types::ResultSet;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
+
+/// A trait for converting a row of JSON values into a specified type.
+///
+/// This mirrors [`FromAthena`] for rows coming from sources other than Athena, such as
+/// `HashMap<String, serde_json::Value>`. It is implemented for every `FromAthena` type,
+/// so a struct deriving `FromAthena` parses JSON rows with the same field attributes.
+///
+/// Each value is rendered back to the text Athena would have returned, see
+/// [`athena_text`], and the row is then converted with [`FromAthena::from_athena`].
+pub trait FromAthenaValue: Sized {
+    /// Converts a HashMap of JSON values into an instance of the implementing type.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - A HashMap containing the data to be converted.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the converted instance of the implementing type or an error if conversion fails.
+    fn from_athena_value(values: HashMap<String, Value>) -> anyhow::Result<Self>;
+}
+
+impl<T: FromAthena> FromAthenaValue for T {
+    fn from_athena_value(values: HashMap<String, Value>) -> anyhow::Result<Self> {
+        let row = values
+            .into_iter()
+            .filter_map(|(col, val)| athena_text(&val).map(|text| (col, text)))
+            .collect();
+
+        T::from_athena(row)
+    }
+}
+
+/// Renders a JSON value as the text Athena uses for the equivalent SQL value.
+///
+/// Strings are used as is, numbers and booleans use their JSON text, arrays become
+/// `[a, b]` and objects become `{key=value}`. `null` has no text and returns `None`,
+/// so the column is treated as missing.
+///
+/// This is the inverse of [`json_value`], so a typed value renders back to the text
+/// Athena returned for it, such as for logging or CSV export.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::{athena_text, json_value};
+///
+/// assert_eq!(athena_text(&json_value("bigint", Some("100"))).unwrap(), "100");
+/// assert_eq!(athena_text(&json_value("boolean", Some("true"))).unwrap(), "true");
+/// assert_eq!(athena_text(&json_value("varchar", None)), None);
+/// ```
+pub fn athena_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+        Value::Array(items) => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| athena_text(item).unwrap_or_else(|| "null".to_string()))
+                .collect();
+            Some(format!("[{}]", items.join(", ")))
+        }
+        Value::Object(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, item)| {
+                    let item = athena_text(item).unwrap_or_else(|| "null".to_string());
+                    format!("{}={}", key, item)
+                })
+                .collect();
+            Some(format!("{{{}}}", entries.join(", ")))
+        }
+    }
+}
+
+/// Builds a vector of JSON objects representing the rows of the given ResultSet.
+///
+/// Each object is keyed by column name. The column type from the metadata decides
+/// the JSON representation of each value, see [`json_value`].
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the data to be converted into JSON objects.
+///
+/// # Returns
+///
+/// A vector of `serde_json::Value::Object`, one per row. If the ResultSet contains
+/// no metadata, an empty vector is returned.
+pub fn build_json_rows(result_set: &ResultSet) -> Vec<Value> {
+    json_rows(result_set, json_value)
+}
+
+/// Serializes the rows of the given ResultSet as newline-delimited JSON.
+///
+/// Each row becomes a JSON object keyed by column name on its own line. Every value is
+/// kept as a JSON string, even when it looks numeric, and a SQL null becomes `null`.
+/// See [`rows_to_ndjson_typed`] to convert values according to their column type.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be serialized.
+///
+/// # Returns
+///
+/// The serialized rows, each followed by a newline. An empty string is returned if the
+/// ResultSet contains no rows or no metadata.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::ResultSet;
+/// use aws_athena_parser::rows_to_ndjson;
+///
+/// let result_set = ResultSet::builder().build();
+/// assert_eq!(rows_to_ndjson(&result_set), "");
+/// ```
+pub fn rows_to_ndjson(result_set: &ResultSet) -> String {
+    ndjson(json_rows(result_set, |_, value| {
+        value.map_or(Value::Null, |value| Value::String(value.to_string()))
+    }))
+}
+
+/// Serializes the rows of the given ResultSet as newline-delimited JSON, converting each
+/// value according to its column type as [`build_json_rows`] does.
+pub fn rows_to_ndjson_typed(result_set: &ResultSet) -> String {
+    ndjson(build_json_rows(result_set))
+}
+
+fn ndjson(rows: Vec<Value>) -> String {
+    rows.iter().map(|row| format!("{}\n", row)).collect()
+}
+
+/// Builds a JSON object per row, converting each value with `convert` given its column type.
+fn json_rows(result_set: &ResultSet, convert: impl Fn(&str, Option<&str>) -> Value) -> Vec<Value> {
+    let Some(meta) = result_set.result_set_metadata() else {
+        return vec![];
+    };
+
+    result_set
+        .rows()
+        .iter()
+        .map(|r| {
+            r.data()
+                .iter()
+                .zip(meta.column_info().iter())
+                .map(|(d, c)| {
+                    (
+                        c.name().to_string(),
+                        convert(c.r#type(), d.var_char_value()),
+                    )
+                })
+                .collect::<Map<String, Value>>()
+        })
+        .map(Value::Object)
+        .collect()
+}
+
+/// Converts a single Athena value into JSON according to its column type.
+///
+/// Integer, floating point and decimal types become numbers, `boolean` becomes a bool
+/// and a SQL null becomes `null`. Everything else, including values that do not parse
+/// as their declared type, is kept as a string.
+pub fn json_value(athena_type: &str, value: Option<&str>) -> Value {
+    let Some(value) = value else {
+        return Value::Null;
+    };
+
+    let base_type = crate::schema::base_type(athena_type);
+
+    let converted = match base_type.as_str() {
+        "tinyint" | "smallint" | "integer" | "int" | "bigint" => {
+            value.parse::<i64>().ok().map(Value::from)
+        }
+        "float" | "real" | "double" | "decimal" => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        "boolean" => value.parse::<bool>().ok().map(Value::Bool),
+        _ => None,
+    };
+
+    converted.unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+/// The text of an Athena `json` column, checked to be well-formed JSON but kept as a string.
+///
+/// Parsing only validates the value, so deserializing it into a concrete type can be left
+/// until it is needed, for example with `serde_json::from_str(json.as_str())`. As a field
+/// of a struct deriving [`FromAthena`], a malformed value fails the row.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::{FromAthena, HashMap, JsonString};
+/// use aws_athena_parser::anyhow;
+///
+/// #[derive(FromAthena)]
+/// struct Event {
+///     payload: JsonString,
+/// }
+///
+/// let row = HashMap::from([("payload".to_string(), r#"{"id": 1}"#.to_string())]);
+/// let event = Event::from_athena(row).unwrap();
+/// assert_eq!(event.payload.to_value().unwrap()["id"], 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct JsonString(String);
+
+impl JsonString {
+    /// Returns the JSON text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the JSON text, consuming the `JsonString`.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    /// Deserializes the JSON text into a `serde_json::Value`.
+    ///
+    /// # Errors
+    ///
+    /// The text was validated when the `JsonString` was parsed, so this only fails for
+    /// values nested deeper than `serde_json` allows.
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        serde_json::from_str(&self.0)
+    }
+}
+
+impl FromStr for JsonString {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str::<Value>(s).map_err(|e| {
+            anyhow::Error::msg(format!(
+                "Invalid JSON within result set. `{}` could not be parsed: {}!",
+                s, e
+            ))
+        })?;
+
+        Ok(JsonString(s.to_string()))
+    }
+}
+
+impl fmt::Display for JsonString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for JsonString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::anyhow;
+    use crate::test_util::result_set_with_nulls;
+    use serde_json::json;
+
+    #[derive(crate::FromAthena)]
+    struct Testing {
+        pub id: i64,
+        pub active: bool,
+        pub name: String,
+        pub tags: Vec<String>,
+        #[athena(percent = "fraction")]
+        pub share: f64,
+    }
+
+    #[derive(crate::FromAthena)]
+    struct JsonStringTesting {
+        pub payload: JsonString,
+    }
+
+    #[test]
+    fn convert_result_set_to_json() {
+        let result_set = result_set_with_nulls(
+            &[("id", "bigint"), ("active", "boolean"), ("note", "varchar")],
+            &[&[Some("100"), Some("true"), None]],
+        );
+
+        let res = build_json_rows(&result_set);
+        assert_eq!(res, vec![json!({"id": 100, "active": true, "note": null})]);
+    }
+
+    #[test]
+    fn converted_json_row_to_struct() {
+        let row = HashMap::from([
+            ("id".to_string(), json!(7)),
+            ("active".to_string(), json!(true)),
+            ("name".to_string(), json!("test")),
+            ("tags".to_string(), json!(["a", "b"])),
+            ("share".to_string(), json!("50%")),
+        ]);
+
+        let res = Testing::from_athena_value(row).unwrap();
+        assert_eq!(res.id, 7);
+        assert!(res.active);
+        assert_eq!(res.name, "test");
+        assert_eq!(res.tags, vec!["a", "b"]);
+        assert_eq!(res.share, 0.5);
+    }
+
+    #[test]
+    fn error_convert_json_null_to_struct() {
+        let row = HashMap::from([
+            ("id".to_string(), json!(null)),
+            ("active".to_string(), json!(true)),
+            ("name".to_string(), json!("test")),
+            ("tags".to_string(), json!([])),
+            ("share".to_string(), json!("50%")),
+        ]);
+
+        let res = Testing::from_athena_value(row);
+        assert_eq!(
+            res.err().map(|e| format!("{:#}", e)).unwrap(),
+            "Testing: Missing field within result set. `id` was not found!".to_string()
+        );
+    }
+
+    #[test]
+    fn convert_result_set_to_ndjson() {
+        let result_set = result_set_with_nulls(
+            &[("id", "bigint"), ("note", "varchar")],
+            &[&[Some("1"), Some("first")], &[Some("2"), None]],
+        );
+
+        assert_eq!(
+            rows_to_ndjson(&result_set),
+            "{\"id\":\"1\",\"note\":\"first\"}\n{\"id\":\"2\",\"note\":null}\n"
+        );
+        assert_eq!(
+            rows_to_ndjson_typed(&result_set),
+            "{\"id\":1,\"note\":\"first\"}\n{\"id\":2,\"note\":null}\n"
+        );
+    }
+
+    #[test]
+    fn typed_values_render_as_athena_text() {
+        let render = |athena_type: &str, value: &str| {
+            athena_text(&json_value(athena_type, Some(value))).unwrap()
+        };
+
+        assert_eq!(render("bigint", "100"), "100");
+        assert_eq!(render("integer", "-7"), "-7");
+        assert_eq!(render("double", "0.5"), "0.5");
+        assert_eq!(render("boolean", "true"), "true");
+        assert_eq!(render("boolean", "false"), "false");
+        assert_eq!(render("varchar", "first"), "first");
+        assert_eq!(render("date", "2024-01-02"), "2024-01-02");
+        assert_eq!(athena_text(&json_value("bigint", None)), None);
+
+        assert_eq!(athena_text(&json!([1, null, "a"])).unwrap(), "[1, null, a]");
+        assert_eq!(
+            athena_text(&json!({"a": 1, "b": [true]})).unwrap(),
+            "{a=1, b=[true]}"
+        );
+    }
+
+    #[test]
+    fn type_aliases_convert_alike() {
+        assert_eq!(
+            json_value("int", Some("7")),
+            json_value("integer", Some("7"))
+        );
+        assert_eq!(json_value("INT", Some("7")), json!(7));
+        assert_eq!(
+            json_value("string", Some("7")),
+            json_value("varchar(10)", Some("7"))
+        );
+    }
+
+    #[test]
+    fn converted_json_string() {
+        let row = HashMap::from([(
+            "payload".to_string(),
+            r#"{"id": 1, "tags": ["a"]}"#.to_string(),
+        )]);
+        let res = JsonStringTesting::from_athena(row).unwrap();
+        assert_eq!(res.payload.as_str(), r#"{"id": 1, "tags": ["a"]}"#);
+        assert_eq!(
+            res.payload.to_value().unwrap(),
+            json!({"id": 1, "tags": ["a"]})
+        );
+
+        let row = HashMap::from([("payload".to_string(), r#"{"id": 1"#.to_string())]);
+        assert_eq!(
+            JsonStringTesting::from_athena(row).err().map(|e| format!("{:#}", e)).unwrap(),
+            "JsonStringTesting: Invalid JSON within result set. `{\"id\": 1` could not be parsed: EOF while parsing an object at line 1 column 8!"
+        );
+    }
+
+    #[test]
+    fn parameterized_types_convert_as_their_base_type() {
+        assert_eq!(json_value("varchar(20)", Some("7")), json!("7"));
+        assert_eq!(json_value("decimal(10,2)", Some("12.50")), json!(12.5));
+        assert_eq!(json_value("varbinary", Some("68 65")), json!("68 65"));
+    }
+}
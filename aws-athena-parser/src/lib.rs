@@ -1,6 +1,37 @@
+extern crate self as aws_athena_parser;
+
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "csv")]
+mod csv;
+pub mod field;
+#[cfg(feature = "json")]
+mod json;
+mod options;
+mod row;
+mod schema;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
 pub use anyhow;
-use aws_sdk_athena::types::ResultSet;
-pub use from_athena_derive::FromAthena;
+use aws_sdk_athena::types::{ColumnInfo, Datum, ResultSet, ResultSetMetadata, Row};
+#[cfg(feature = "csv")]
+pub use csv::{csv_to_rows, parse_csv, rows_to_csv};
+pub use from_athena_derive::{FromAthena, ToAthena};
+#[cfg(feature = "ordered")]
+pub use indexmap::IndexMap;
+#[cfg(feature = "json")]
+pub use json::{
+    athena_text, build_json_rows, json_value, rows_to_ndjson, rows_to_ndjson_typed,
+    FromAthenaValue, JsonString,
+};
+pub use options::ParserOptions;
+pub use row::{AthenaRowExt, RowSource};
+pub use schema::{
+    column_meta, column_names, columns_match_struct, diff_schema, overlapping_columns, ColumnMeta,
+    ColumnSpec, Mismatch, SchemaDiff,
+};
+use std::any::Any;
 pub use std::collections::HashMap;
 
 /// A trait for converting data from an Athena query result into a specified type.
@@ -10,6 +41,9 @@ pub use std::collections::HashMap;
 /// of the implementing type. The conversion process may involve parsing, validation,
 /// or any other necessary transformation.
 ///
+/// Columns that the implementing type does not read are ignored, so a struct may
+/// map only a subset of the columns returned by a query.
+///
 /// # Errors
 ///
 /// If the conversion process fails due to invalid or missing data, an error is returned.
@@ -28,6 +62,222 @@ pub trait FromAthena: Sized {
     ///
     /// Result containing the converted instance of the implementing type or an error if conversion fails.
     fn from_athena(values: HashMap<String, String>) -> anyhow::Result<Self, anyhow::Error>;
+
+    /// Returns the names of the columns read by `from_athena`.
+    ///
+    /// The derive macro generates this from the struct's fields. Manual implementations
+    /// that do not override it report no columns.
+    fn athena_columns() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Returns the fields read by column index rather than column name.
+    ///
+    /// Each entry pairs a column index with the column name the field reads otherwise.
+    /// The parse functions of this crate read the value at each index from the row itself
+    /// and pass them, in the same order, to `from_athena_positional`. The derive macro
+    /// generates this from `#[athena(position = N)]`.
+    fn athena_positions() -> &'static [(usize, &'static str)] {
+        &[]
+    }
+
+    /// Returns what each column read by the struct requires of the result set, for
+    /// [`columns_match_struct`].
+    ///
+    /// The derive macro generates this from the struct's fields. Manual implementations
+    /// that do not override it report no requirements.
+    fn athena_column_specs() -> &'static [ColumnSpec] {
+        &[]
+    }
+
+    /// Converts a row as `from_athena` does, given the Athena type of each column.
+    ///
+    /// `types` maps column names to Athena types such as `bigint`. The parse functions of
+    /// this crate call this rather than `from_athena`. The derive macro uses the types
+    /// for `#[athena(flatten)]` fields of type `serde_json::Map`, and manual
+    /// implementations ignore them unless they override this.
+    fn from_athena_with_types(
+        values: HashMap<String, String>,
+        types: &HashMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        let _ = types;
+        Self::from_athena(values)
+    }
+
+    /// Converts a row given as `(name, type, value)` triples, such as
+    /// `("id", "bigint", "1")`, as `from_athena_with_types` does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aws_athena_parser::{anyhow, FromAthena, HashMap};
+    ///
+    /// #[derive(FromAthena)]
+    /// struct MyStruct {
+    ///     my_value: i64,
+    /// }
+    ///
+    /// let row = vec![("my_value".to_string(), "bigint".to_string(), "1".to_string())];
+    /// let parsed = MyStruct::from_athena_typed(row).unwrap();
+    /// assert_eq!(parsed.my_value, 1);
+    /// ```
+    fn from_athena_typed(row: Vec<(String, String, String)>) -> anyhow::Result<Self> {
+        let mut values = HashMap::with_capacity(row.len());
+        let mut types = HashMap::with_capacity(row.len());
+
+        for (name, athena_type, value) in row {
+            types.insert(name.clone(), athena_type);
+            values.insert(name, value);
+        }
+
+        Self::from_athena_ref(&values, &types)
+    }
+
+    /// Converts a borrowed row as `from_athena_with_types` does.
+    ///
+    /// The derive macro implements this without copying the row. Manual implementations
+    /// copy the row into `from_athena_with_types` unless they override this.
+    fn from_athena_ref(
+        values: &HashMap<String, String>,
+        types: &HashMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        Self::from_athena_with_types(values.clone(), types)
+    }
+
+    /// Converts a borrowed row of any [`RowSource`] as `from_athena_ref` does, such as an
+    /// `IndexMap` built by [`build_ordered_map`] with the `ordered` feature.
+    ///
+    /// The derive macro implements this without copying the row. Manual implementations
+    /// copy the row into `from_athena_with_types` unless they override this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aws_athena_parser::{anyhow, FromAthena, HashMap};
+    ///
+    /// #[derive(FromAthena)]
+    /// struct MyStruct {
+    ///     my_value: i64,
+    /// }
+    ///
+    /// let row = HashMap::from([("my_value".to_string(), "1".to_string())]);
+    /// let parsed = MyStruct::from_athena_source(&row, &HashMap::new()).unwrap();
+    /// assert_eq!(parsed.my_value, 1);
+    /// ```
+    fn from_athena_source<R: RowSource + ?Sized>(
+        row: &R,
+        types: &HashMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        let values = row::entries(row)
+            .map(|(column, value)| (column.to_string(), value.to_string()))
+            .collect();
+        Self::from_athena_with_types(values, types)
+    }
+
+    /// Converts a borrowed row as `from_athena_source` does, given the values of the
+    /// columns at the indexes of `athena_positions`, in the same order.
+    ///
+    /// A `None` value is a SQL NULL, or an index past the last column. When `positional`
+    /// is empty, fields read by index read their column by name instead, as they do with
+    /// every other conversion. Manual implementations ignore `positional` unless they
+    /// override this.
+    fn from_athena_positional<R: RowSource + ?Sized>(
+        row: &R,
+        positional: &[Option<&str>],
+        types: &HashMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        let _ = positional;
+        Self::from_athena_source(row, types)
+    }
+
+    /// Converts a row as `from_athena` does, also returning the row itself.
+    ///
+    /// This keeps the raw values available, for example for auditing, without the caller
+    /// copying the row first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aws_athena_parser::{anyhow, FromAthena, HashMap};
+    ///
+    /// #[derive(FromAthena)]
+    /// struct MyStruct {
+    ///     my_value: String,
+    /// }
+    ///
+    /// let row = HashMap::from([("my_value".to_string(), "test".to_string())]);
+    /// let (parsed, raw) = MyStruct::from_athena_with_raw(row).unwrap();
+    /// assert_eq!(raw["my_value"], parsed.my_value);
+    /// ```
+    fn from_athena_with_raw(
+        values: HashMap<String, String>,
+    ) -> anyhow::Result<(Self, HashMap<String, String>)> {
+        let parsed = Self::from_athena_ref(&values, &HashMap::new())?;
+        Ok((parsed, values))
+    }
+}
+
+/// Passes a row through unchanged, so generic code over `T: FromAthena` can ask for the
+/// raw rows.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::{FromAthena, HashMap};
+///
+/// let row = HashMap::from([("id".to_string(), "1".to_string())]);
+/// assert_eq!(HashMap::<String, String>::from_athena(row.clone()).unwrap(), row);
+/// ```
+impl FromAthena for HashMap<String, String> {
+    fn from_athena(values: HashMap<String, String>) -> anyhow::Result<Self> {
+        Ok(values)
+    }
+}
+
+/// A field that failed to parse within `from_athena_lenient`, generated for structs
+/// deriving `FromAthena` with `#[athena(lenient)]`.
+#[derive(Debug)]
+pub struct FieldError {
+    /// The column the field is read from.
+    pub field: &'static str,
+    /// Why the value could not be parsed, with the struct name or its
+    /// `#[athena(error_prefix)]` as context.
+    pub error: anyhow::Error,
+}
+
+/// A trait for converting a type into a row of Athena values, the inverse of [`FromAthena`].
+///
+/// Deriving `ToAthena` renders each field as the text Athena would return for it, so the
+/// row parses back with the matching `FromAthena` implementation. Together with
+/// [`to_result_set`] this builds a `ResultSet` for testing code that consumes one.
+pub trait ToAthena {
+    /// Converts the implementing type into a HashMap of column names to values.
+    ///
+    /// # Returns
+    ///
+    /// A HashMap with one entry per column that has a value. Columns without one, such as
+    /// an `Option` field that is `None`, are left out.
+    fn to_athena(&self) -> HashMap<String, String>;
+}
+
+/// Returns the columns expected by `T` that are not present in the given row.
+///
+/// This allows checking a row before attempting to parse it with [`FromAthena::from_athena`].
+///
+/// # Arguments
+///
+/// * `row` - A row as produced by [`build_map`].
+///
+/// # Returns
+///
+/// The names of the columns from [`FromAthena::athena_columns`] missing from `row`,
+/// in field declaration order.
+pub fn missing_columns<T: FromAthena>(row: &HashMap<String, String>) -> Vec<&'static str> {
+    T::athena_columns()
+        .iter()
+        .copied()
+        .filter(|col| !row.contains_key(*col))
+        .collect()
 }
 
 /// Builds a vector of hash maps representing the rows of the given ResultSet.
@@ -36,6 +286,14 @@ pub trait FromAthena: Sized {
 /// where each hash map represents a row in the ResultSet. If the ResultSet contains
 /// no data or metadata, an empty vector is returned.
 ///
+/// Athena returns the values of a row in column order, so each value is paired with
+/// the column at the same position in the metadata. The resulting maps are keyed by
+/// column name only, so the field order of a struct built from them is irrelevant.
+///
+/// SQL NULL values are left out of the maps, so an `Option<T>` field reading them is
+/// `None`, while an empty string is kept. Columns of the `unknown` type, which Athena
+/// reports for `SELECT NULL AS x`, are left out altogether.
+///
 /// # Arguments
 ///
 /// * `result_set` - A ResultSet containing the data to be converted into hash maps.
@@ -48,164 +306,1557 @@ pub trait FromAthena: Sized {
 ///
 /// ```
 /// use aws_sdk_athena::types::ResultSet;
-/// use aws_sdk_athena::build_map;
+/// use aws_athena_parser::build_map;
 ///
-/// let result_set = ResultSet::new(/* Some initialization */);
+/// let result_set = ResultSet::builder().build();
 /// let mapped_data = build_map(result_set);
 /// // Use mapped_data for further processing
 /// ```
 pub fn build_map(result_set: ResultSet) -> Vec<HashMap<String, String>> {
-    if let Some(meta) = result_set.result_set_metadata() {
-        let columns: Vec<String> = meta
-            .column_info()
-            .iter()
-            .map(|c| c.name().to_string())
-            .collect();
+    map_rows(&result_set, false, &ParserOptions::default())
+}
 
-        let rows: Vec<HashMap<String, String>> = result_set
-            .rows()
-            .iter()
-            .map(|r| {
-                r.data()
-                    .iter()
-                    .map(|d| d.var_char_value().unwrap_or("").to_string())
-                    .zip(columns.iter())
-                    .map(|(val, col)| (col.clone(), val.clone()))
-                    .collect::<HashMap<String, String>>()
-            })
-            .collect();
+/// Builds a vector of hash maps as [`build_map`] does, borrowing the column names and values
+/// from the ResultSet instead of copying them.
+///
+/// The maps cannot outlive the ResultSet. They can be parsed with
+/// [`FromAthena::from_athena_source`] without allocating a `String` per value.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the data to be viewed as hash maps.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::ResultSet;
+/// use aws_athena_parser::build_map_borrowed;
+///
+/// let result_set = ResultSet::builder().build();
+/// let mapped_data = build_map_borrowed(&result_set);
+/// assert!(mapped_data.is_empty());
+/// ```
+pub fn build_map_borrowed(result_set: &ResultSet) -> Vec<HashMap<&str, &str>> {
+    let Some(meta) = result_set.result_set_metadata() else {
+        return vec![];
+    };
 
-        rows
-    } else {
-        vec![]
+    // As in `map_rows`, columns of the `unknown` type and NULL values are left out.
+    let columns: Vec<Option<&str>> = meta
+        .column_info()
+        .iter()
+        .map(|c| (!is_untyped(c.r#type())).then_some(c.name()))
+        .collect();
+
+    result_set
+        .rows()
+        .iter()
+        .map(|r| {
+            columns
+                .iter()
+                .zip(r.data().iter())
+                .filter_map(|(column, d)| Some(((*column)?, d.var_char_value()?)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Builds a vector of hash maps as [`build_map`] does, failing when the ResultSet has rows
+/// but no metadata.
+///
+/// `build_map` returns no rows for such a ResultSet, the same as for one without any rows.
+/// Rows without metadata usually mean the results were fetched from a partial response,
+/// such as before the query finished, so this reports it instead.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the data to be converted into hash maps.
+///
+/// # Errors
+///
+/// Returns an error if the ResultSet has rows but no `result_set_metadata`.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::{Datum, ResultSet, Row};
+/// use aws_athena_parser::try_build_map;
+///
+/// assert!(try_build_map(ResultSet::builder().build()).unwrap().is_empty());
+///
+/// let row = Row::builder()
+///     .data(Datum::builder().var_char_value("100").build())
+///     .build();
+/// assert!(try_build_map(ResultSet::builder().rows(row).build()).is_err());
+/// ```
+pub fn try_build_map(result_set: ResultSet) -> anyhow::Result<Vec<HashMap<String, String>>> {
+    if result_set.result_set_metadata().is_none() && !result_set.rows().is_empty() {
+        return Err(missing_metadata(result_set.rows().len()));
     }
+
+    Ok(build_map(result_set))
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use aws_sdk_athena::types::{ColumnInfo, Datum, ResultSetMetadata, Row};
+/// Builds the error for a ResultSet holding `rows` rows but no metadata.
+pub(crate) fn missing_metadata(rows: usize) -> anyhow::Error {
+    anyhow::Error::msg(format!(
+        "Missing metadata within result set. {} rows were returned without column information!",
+        rows
+    ))
+}
 
-    #[derive(from_athena_derive::FromAthena)]
-    struct Testing {
-        pub test: i64,
-    }
+/// Builds the rows of the given ResultSet as [`build_map`] does, keeping the columns of
+/// each row in the order of the result set.
+///
+/// The rows can be parsed with [`FromAthena::from_athena_source`] without converting them
+/// back into a `HashMap`. Requires the `ordered` feature.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::ResultSet;
+/// use aws_athena_parser::build_ordered_map;
+///
+/// let result_set = ResultSet::builder().build();
+/// let ordered_data = build_ordered_map(result_set);
+/// ```
+#[cfg(feature = "ordered")]
+pub fn build_ordered_map(result_set: ResultSet) -> Vec<IndexMap<String, String>> {
+    let columns = row_columns(&result_set, &ParserOptions::default());
 
-    #[derive(from_athena_derive::FromAthena)]
-    #[allow(dead_code)]
-    struct BadTesting {
-        pub no_exist: String,
-    }
+    let rows = match result_set.result_set_metadata() {
+        Some(_) => result_set.rows(),
+        None => &[],
+    };
 
-    #[derive(from_athena_derive::FromAthena)]
-    struct LargeStruct {
-        pub test1: i64,
-        pub test2: i32,
-        pub test3: String,
-        pub test4: String,
-        pub test5: f64,
-        pub test6: bool,
+    rows.iter()
+        .map(|r| row_entries(&columns, r).collect())
+        .collect()
+}
+
+/// Builds a vector of hash maps representing the rows of the given ResultSet, as
+/// [`build_map`] does, using the provided options.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the data to be converted into hash maps.
+/// * `options` - The [`ParserOptions`] controlling how column names are read.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::ResultSet;
+/// use aws_athena_parser::{build_map_with_options, ParserOptions};
+///
+/// let result_set = ResultSet::builder().build();
+/// let options = ParserOptions::new().unquote_column_names(true);
+/// let mapped_data = build_map_with_options(result_set, &options);
+/// ```
+pub fn build_map_with_options(
+    result_set: ResultSet,
+    options: &ParserOptions,
+) -> Vec<HashMap<String, String>> {
+    let mut rows = map_rows(&result_set, false, options);
+
+    if options.blank_as_null {
+        for row in rows.iter_mut() {
+            // Nothing is required, so blank values are only ever removed.
+            let _ = remove_blanks(row, &[]);
+        }
     }
 
-    #[test]
-    fn convert_result_set_to_map() {
-        let column = ColumnInfo::builder()
-            .name("test")
-            .r#type("bigint")
-            .build()
-            .unwrap();
-        let metadata = ResultSetMetadata::builder().column_info(column).build();
-        let data = Datum::builder()
-            .set_var_char_value(Some("100".to_string()))
-            .build();
-        let row = Row::builder().set_data(Some(vec![data])).build();
-        let result_set = ResultSet::builder()
-            .result_set_metadata(metadata)
-            .set_rows(Some(vec![row]))
-            .build();
+    rows
+}
 
-        let res = build_map(result_set);
-        assert!(res.len() == 1);
-        assert!(res[0].get("test").is_some());
-        assert_eq!(res[0].get("test").unwrap(), "100");
+/// Builds the rows of the given ResultSet as [`build_map`] does, into a caller-owned buffer.
+///
+/// The buffer is cleared and then filled with the rows of `result_set`, so its allocation
+/// can be reused across the pages of a query's results.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the data to be converted into hash maps.
+/// * `buffer` - The vector the rows are written to.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::ResultSet;
+/// use aws_athena_parser::build_map_into;
+///
+/// let mut rows = Vec::new();
+/// for page in [ResultSet::builder().build(), ResultSet::builder().build()] {
+///     build_map_into(&page, &mut rows);
+///     // Use rows for further processing
+/// }
+/// ```
+pub fn build_map_into(result_set: &ResultSet, buffer: &mut Vec<HashMap<String, String>>) {
+    buffer.clear();
+    map_rows_into(result_set, false, &ParserOptions::default(), buffer);
+}
+
+/// Builds the rows of the given ResultSet keyed by the value of `key_column`.
+///
+/// Rows are built as with [`build_map`], and each is stored under its own value of
+/// `key_column`.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the data to be converted into hash maps.
+/// * `key_column` - The column whose value keys each row.
+/// * `keep_last` - Whether a row whose key was already seen replaces the earlier row,
+///   rather than being an error.
+///
+/// # Errors
+///
+/// Returns an error if a row has no value for `key_column`, or if two rows share a key
+/// and `keep_last` is not set.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::ResultSet;
+/// use aws_athena_parser::build_map_indexed_by;
+///
+/// let result_set = ResultSet::builder().build();
+/// let rows = build_map_indexed_by(result_set, "id", false).unwrap();
+/// assert!(rows.get("1").is_none());
+/// ```
+pub fn build_map_indexed_by(
+    result_set: ResultSet,
+    key_column: &str,
+    keep_last: bool,
+) -> anyhow::Result<HashMap<String, HashMap<String, String>>> {
+    let mut indexed = HashMap::new();
+
+    for row in map_rows(&result_set, false, &ParserOptions::default()) {
+        let key = row
+            .get(key_column)
+            .ok_or_else(|| field::missing_field(key_column))?
+            .clone();
+
+        if indexed.insert(key.clone(), row).is_some() && !keep_last {
+            return Err(anyhow::Error::msg(format!(
+                "Duplicate key within result set. `{}` has `{}` more than once!",
+                key_column, key
+            )));
+        }
     }
 
-    #[test]
-    fn converted_results_to_struct() {
-        let column = ColumnInfo::builder()
-            .name("test")
-            .r#type("bigint")
-            .build()
-            .unwrap();
-        let metadata = ResultSetMetadata::builder().column_info(column).build();
-        let data = Datum::builder()
-            .set_var_char_value(Some("100".to_string()))
-            .build();
-        let row = Row::builder().set_data(Some(vec![data])).build();
-        let result_set = ResultSet::builder()
-            .result_set_metadata(metadata)
-            .set_rows(Some(vec![row]))
-            .build();
+    Ok(indexed)
+}
 
-        let res: Vec<Testing> = build_map(result_set)
-            .iter()
-            .flat_map(|x| Testing::from_athena(x.clone()))
-            .collect();
+/// Maps the rows of a ResultSet as [`build_map`] does, optionally dropping a leading header row.
+pub(crate) fn map_rows(
+    result_set: &ResultSet,
+    skip_header: bool,
+    options: &ParserOptions,
+) -> Vec<HashMap<String, String>> {
+    let mut rows = Vec::with_capacity(result_set.rows().len());
+    map_rows_into(result_set, skip_header, options, &mut rows);
+    rows
+}
 
-        assert_eq!(res[0].test, 100);
+/// Maps the rows of a ResultSet as [`map_rows`] does, appending them to `out`.
+fn map_rows_into(
+    result_set: &ResultSet,
+    skip_header: bool,
+    options: &ParserOptions,
+    out: &mut Vec<HashMap<String, String>>,
+) {
+    out.extend(map_rows_iter(result_set, skip_header, &[], options).map(|row| row.values));
+}
+
+/// A row mapped by [`map_rows_iter`], along with the values of the columns read by index.
+pub(crate) struct MappedRow {
+    pub(crate) values: HashMap<String, String>,
+    positional: Vec<Option<String>>,
+}
+
+impl MappedRow {
+    /// Converts the row into `T`, reading the fields of [`FromAthena::athena_positions`]
+    /// from the values taken by index.
+    pub(crate) fn parse<T: FromAthena>(self, types: &HashMap<String, String>) -> anyhow::Result<T> {
+        if self.positional.is_empty() {
+            return T::from_athena_with_types(self.values, types);
+        }
+
+        let positional: Vec<Option<&str>> = self.positional.iter().map(Option::as_deref).collect();
+        T::from_athena_positional(&self.values, &positional, types)
     }
+}
 
-    #[test]
-    fn converted_results_to_large_struct() {
-        let columns = [
-            ("test1", "bigint"),
-            ("test2", "integer"),
-            ("test3", "varchar"),
-            ("test4", "varchar"),
-            ("test5", "double"),
-            ("test6", "boolean"),
-        ]
+/// Maps the rows of a ResultSet as [`map_rows`] does, one row at a time as the iterator
+/// is advanced.
+///
+/// The value of the column at each index in `positions` is read from the row itself,
+/// whatever the name or type of that column, see [`FromAthena::athena_positions`].
+pub(crate) fn map_rows_iter<'a>(
+    result_set: &'a ResultSet,
+    skip_header: bool,
+    positions: &'a [(usize, &'a str)],
+    options: &ParserOptions,
+) -> impl Iterator<Item = MappedRow> + 'a {
+    let meta = result_set.result_set_metadata();
+
+    let names: Vec<String> = meta
+        .map(|meta| meta.column_info())
+        .unwrap_or_default()
         .iter()
-        .map(|i| {
-            ColumnInfo::builder()
-                .name(i.0.to_string())
-                .r#type(i.1.to_string())
-                .build()
-                .unwrap()
-        })
+        .map(|c| column_name(c, options.key_by_label))
         .collect();
 
-        let metadata = ResultSetMetadata::builder()
-            .set_column_info(Some(columns))
-            .build();
+    let skip = match result_set.rows().first() {
+        Some(first) if skip_header && is_header_row(first, &names) => 1,
+        _ => 0,
+    };
 
-        let data: Vec<Datum> = ["1000", "100", "test", "test", "100.0", "true"]
-            .iter()
-            .map(|v| {
-                Datum::builder()
-                    .set_var_char_value(Some(v.to_string()))
-                    .build()
-            })
-            .collect();
+    let columns = row_columns(result_set, options);
+    let skip_repeated_headers = options.skip_repeated_headers;
+    let sanitize = options.sanitize;
 
-        let row = Row::builder().set_data(Some(data)).build();
+    let rows = match meta {
+        Some(_) => result_set.rows(),
+        None => &[],
+    };
 
-        let result_set = ResultSet::builder()
-            .result_set_metadata(metadata)
-            .set_rows(Some(vec![row]))
-            .build();
+    rows.iter()
+        .skip(skip)
+        .filter(move |r| !(skip_repeated_headers && is_header_row(r, &names)))
+        .map(move |r| {
+            let mut values: HashMap<String, String> = row_entries(&columns, r).collect();
+            let mut positional: Vec<Option<String>> = positions
+                .iter()
+                .map(|(index, _)| {
+                    r.data()
+                        .get(*index)
+                        .and_then(|d| d.var_char_value())
+                        .map(str::to_string)
+                })
+                .collect();
+            if sanitize {
+                values.values_mut().for_each(options::sanitize);
+                positional.iter_mut().flatten().for_each(options::sanitize);
+            }
+            MappedRow { values, positional }
+        })
+}
 
-        let res: Vec<Result<LargeStruct, anyhow::Error>> = build_map(result_set)
-            .iter()
-            .map(|x| LargeStruct::from_athena(x.clone()))
-            .collect();
+/// Returns the name each column of a ResultSet is keyed by in the rows from [`map_rows`],
+/// or `None` for a column left out of them.
+fn row_columns(result_set: &ResultSet, options: &ParserOptions) -> Vec<Option<String>> {
+    let Some(meta) = result_set.result_set_metadata() else {
+        return vec![];
+    };
 
-        for res in res {
-            match res {
-                Ok(r) => {
-                    assert_eq!(r.test1, 1000);
-                    assert_eq!(r.test2, 100);
-                    assert_eq!(r.test3, "test".to_string());
+    meta.column_info()
+        .iter()
+        .map(|c| {
+            // Columns such as `SELECT NULL AS x` are typed `unknown` and only ever hold
+            // NULL, so they are left out of the rows altogether.
+            if is_untyped(c.r#type()) {
+                return None;
+            }
+
+            let name = column_name(c, options.key_by_label);
+            Some(if options.unquote_column_names {
+                options::unquote(&name)
+            } else {
+                name
+            })
+        })
+        .collect()
+}
+
+/// Pairs the values of a row with the columns from [`row_columns`], skipping the columns
+/// left out. `NULL` values are skipped too, as with [`row_to_map`].
+fn row_entries<'a>(
+    columns: &'a [Option<String>],
+    row: &'a Row,
+) -> impl Iterator<Item = (String, String)> + 'a {
+    columns
+        .iter()
+        .zip(row.data().iter())
+        .filter_map(|(col, d)| Some((col.as_ref()?.clone(), d.var_char_value()?.to_string())))
+}
+
+/// Returns the Athena type of each column of a ResultSet, keyed by column name as the
+/// rows from [`map_rows`] are, with any [`ParserOptions::type_overrides`] applied.
+pub(crate) fn column_types(
+    result_set: &ResultSet,
+    options: &ParserOptions,
+) -> HashMap<String, String> {
+    let Some(meta) = result_set.result_set_metadata() else {
+        return HashMap::new();
+    };
+
+    meta.column_info()
+        .iter()
+        .map(|c| {
+            let name = column_name(c, options.key_by_label);
+            let name = if options.unquote_column_names {
+                options::unquote(&name)
+            } else {
+                name
+            };
+            let athena_type = options
+                .type_overrides
+                .get(&name)
+                .cloned()
+                .unwrap_or_else(|| c.r#type().to_string());
+            (name, athena_type)
+        })
+        .collect()
+}
+
+/// Returns the name rows are keyed by for a column, which is its label when `by_label` is
+/// set and the column has a non-empty one.
+fn column_name(column: &ColumnInfo, by_label: bool) -> String {
+    match column.label() {
+        Some(label) if by_label && !label.is_empty() => label.to_string(),
+        _ => column.name().to_string(),
+    }
+}
+
+/// Returns whether a column type is the `unknown` type Athena reports for a bare `NULL`.
+fn is_untyped(athena_type: &str) -> bool {
+    athena_type.eq_ignore_ascii_case("unknown") || athena_type.eq_ignore_ascii_case("null")
+}
+
+/// Builds a hash map of a single row, keyed by column name.
+///
+/// Each value is paired with the column at the same position in `columns`. Values
+/// without a column and `NULL` values are left out, so an `Option<T>` field reading them
+/// is `None`.
+///
+/// # Arguments
+///
+/// * `row` - The row to be converted.
+/// * `columns` - The column names of the ResultSet the row belongs to, in order.
+///
+/// # Returns
+///
+/// A hash map from column name to the value of that column in the row.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::{Datum, Row};
+/// use aws_athena_parser::row_to_map;
+///
+/// let row = Row::builder()
+///     .data(Datum::builder().var_char_value("100").build())
+///     .build();
+/// let map = row_to_map(&row, &["test".to_string()]);
+/// assert_eq!(map["test"], "100");
+/// ```
+pub fn row_to_map(row: &Row, columns: &[String]) -> HashMap<String, String> {
+    columns
+        .iter()
+        .zip(row.data().iter())
+        .filter_map(|(col, d)| Some((col.clone(), d.var_char_value()?.to_string())))
+        .collect()
+}
+
+/// Returns whether a row is the header row Athena returns ahead of the data on the
+/// first page of a query's results, that is every value equals its column name.
+pub(crate) fn is_header_row(row: &Row, columns: &[String]) -> bool {
+    !columns.is_empty()
+        && row.data().len() == columns.len()
+        && row
+            .data()
+            .iter()
+            .zip(columns.iter())
+            .all(|(d, col)| d.var_char_value() == Some(col.as_str()))
+}
+
+/// Parses every row of the given ResultSet into `T`.
+///
+/// Only the columns read by `T` are looked at. Any other column in the ResultSet is
+/// ignored, even when its value would not parse into any type.
+///
+/// Every row is parsed, including a header row Athena returns ahead of the data on the
+/// first page of results. See [`ParserOptions::skip_header`] to drop it instead.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+///
+/// # Errors
+///
+/// Returns the error of the first row that could not be converted.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::{parse_result_set, FromAthena, HashMap, anyhow};
+/// use aws_sdk_athena::types::ResultSet;
+///
+/// #[derive(FromAthena)]
+/// struct MyStruct {
+///     my_value: String,
+/// }
+///
+/// let result_set = ResultSet::builder().build();
+/// let rows: Vec<MyStruct> = parse_result_set(result_set).unwrap();
+/// ```
+pub fn parse_result_set<T: FromAthena>(result_set: ResultSet) -> anyhow::Result<Vec<T>> {
+    parse_result_set_with_options(result_set, &ParserOptions::default())
+}
+
+/// Parses every row of the given ResultSet into `T`, dropping every header-like row.
+///
+/// As [`parse_result_set`], except that any row whose values all equal their column
+/// names is skipped, not only a leading one, see [`ParserOptions::skip_repeated_headers`].
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+///
+/// # Errors
+///
+/// Returns the error of the first remaining row that could not be converted.
+pub fn parse_result_set_dedup_header<T: FromAthena>(
+    result_set: ResultSet,
+) -> anyhow::Result<Vec<T>> {
+    parse_result_set_with_options(
+        result_set,
+        &ParserOptions::new().skip_repeated_headers(true),
+    )
+}
+
+/// Parses every row of the given ResultSet into `T` using the provided options.
+///
+/// Rows are built as with [`build_map`], skipping a leading header row when
+/// [`ParserOptions::skip_header`] is set, and converted with
+/// [`FromAthena::from_athena_with_types`]. The first row that fails to convert stops
+/// parsing and its error is returned.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+/// * `options` - The [`ParserOptions`] controlling how rows are parsed.
+///
+/// # Errors
+///
+/// Returns the error of the first row that could not be converted. When
+/// [`ParserOptions::include_row_in_error`] is set, the error also carries a
+/// truncated summary of the offending row. When [`ParserOptions::validate_decimals`] is
+/// set, a decimal value that does not fit its column type is also an error.
+pub fn parse_result_set_with_options<T: FromAthena>(
+    result_set: ResultSet,
+    options: &ParserOptions,
+) -> anyhow::Result<Vec<T>> {
+    let types = column_types(&result_set, options);
+    let required: Vec<&str> = T::athena_column_specs()
+        .iter()
+        .filter(|spec| spec.required)
+        .map(|spec| spec.name)
+        .collect();
+
+    map_rows_iter(
+        &result_set,
+        options.skip_header,
+        T::athena_positions(),
+        options,
+    )
+    .enumerate()
+    .map(|(index, mut row)| {
+        let summary = options
+            .include_row_in_error
+            .then(|| options::summarize_row(&row.values));
+
+        let validated = if options.validate_decimals {
+            validate_decimals(&row.values, &types)
+        } else {
+            Ok(())
+        };
+        let validated = validated.and_then(|()| {
+            if options.blank_as_null {
+                remove_blanks(&mut row.values, &required)
+            } else {
+                Ok(())
+            }
+        });
+
+        validated
+            .and_then(|()| row.parse(&types))
+            .map_err(|e| match summary {
+                Some(summary) => e.context(format!("Failed to parse row {}: {}", index, summary)),
+                None => e,
+            })
+    })
+    .collect()
+}
+
+/// Removes the values of a row made only of whitespace, for [`ParserOptions::blank_as_null`].
+///
+/// A blank value in one of the `required` columns is an error instead.
+fn remove_blanks(row: &mut HashMap<String, String>, required: &[&str]) -> anyhow::Result<()> {
+    if let Some(column) = required.iter().find(|column| {
+        row.get(**column)
+            .is_some_and(|value| value.trim().is_empty())
+    }) {
+        return Err(anyhow::Error::msg(format!(
+            "Blank value within result set. `{}` has no value!",
+            column
+        )));
+    }
+
+    row.retain(|_, value| !value.trim().is_empty());
+    Ok(())
+}
+
+/// Checks every value of a `decimal(p,s)` column against its declared precision and scale.
+fn validate_decimals(
+    row: &HashMap<String, String>,
+    types: &HashMap<String, String>,
+) -> anyhow::Result<()> {
+    for (column, athena_type) in types {
+        let Some((precision, scale)) = field::decimal_precision_scale(athena_type) else {
+            continue;
+        };
+        match row.get(column) {
+            Some(value) if !value.is_empty() => {
+                field::validate_decimal(column, value, precision, scale)?
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses at most `limit` rows of the given ResultSet into `T`.
+///
+/// Rows are built and converted as with [`parse_result_set`], one at a time, and parsing
+/// stops once `limit` rows were converted. Rows past the limit are never mapped, which
+/// makes this cheap for previewing a large ResultSet.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+/// * `limit` - The most rows to parse.
+///
+/// # Errors
+///
+/// Returns the error of the first row within the limit that could not be converted.
+pub fn parse_result_set_limited<T: FromAthena>(
+    result_set: ResultSet,
+    limit: usize,
+) -> anyhow::Result<Vec<T>> {
+    let options = ParserOptions::default();
+    let types = column_types(&result_set, &options);
+
+    map_rows_iter(&result_set, false, T::athena_positions(), &options)
+        .take(limit)
+        .map(|row| row.parse(&types))
+        .collect()
+}
+
+/// Counts of the rows handled by [`parse_result_set_with_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    /// Rows converted into `T`.
+    pub parsed: usize,
+    /// Rows that failed to convert and were left out of the results.
+    pub failed: usize,
+    /// Rows not converted at all, such as the rows of a ResultSet without metadata.
+    pub skipped: usize,
+}
+
+/// Parses every row of the given ResultSet into `T`, counting the rows parsed and failed.
+///
+/// Rows are built and converted as with [`parse_result_set`], except that a row which
+/// fails to convert is counted and left out instead of stopping parsing.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+///
+/// # Returns
+///
+/// The rows that converted, along with [`ParseStats`] counting every row of the ResultSet.
+pub fn parse_result_set_with_stats<T: FromAthena>(result_set: ResultSet) -> (Vec<T>, ParseStats) {
+    let options = ParserOptions::default();
+    let types = column_types(&result_set, &options);
+    let rows: Vec<MappedRow> =
+        map_rows_iter(&result_set, false, T::athena_positions(), &options).collect();
+
+    let mut stats = ParseStats {
+        skipped: result_set.rows().len() - rows.len(),
+        ..ParseStats::default()
+    };
+
+    let parsed = rows
+        .into_iter()
+        .filter_map(|row| match row.parse(&types) {
+            Ok(parsed) => {
+                stats.parsed += 1;
+                Some(parsed)
+            }
+            Err(_) => {
+                stats.failed += 1;
+                None
+            }
+        })
+        .collect();
+
+    (parsed, stats)
+}
+
+/// Parses every row of the given ResultSet into `T`, grouped by the value of a key column.
+///
+/// Rows are grouped by the raw text of `key_column`, and keep their relative order
+/// within each group. The key column does not need to be read by `T`.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+/// * `key_column` - The name of the column whose value the rows are grouped by.
+///
+/// # Errors
+///
+/// Returns an error if a row does not contain `key_column`, or the error of the first
+/// row that could not be converted.
+pub fn parse_result_set_grouped<T: FromAthena>(
+    result_set: ResultSet,
+    key_column: &str,
+) -> anyhow::Result<HashMap<String, Vec<T>>> {
+    let mut groups: HashMap<String, Vec<T>> = HashMap::new();
+    let types = column_types(&result_set, &ParserOptions::default());
+
+    for row in map_rows_iter(
+        &result_set,
+        false,
+        T::athena_positions(),
+        &ParserOptions::default(),
+    ) {
+        let key = row.values.get(key_column).cloned().ok_or_else(|| {
+            anyhow::Error::msg(format!(
+                "Missing key column within result set. `{}` was not found!",
+                key_column
+            ))
+        })?;
+
+        groups.entry(key).or_default().push(row.parse(&types)?);
+    }
+
+    Ok(groups)
+}
+
+/// Parses every row of the given ResultSet into `T`, keyed by `key_fn`.
+///
+/// Rows are converted as with [`parse_result_set`] and stored under the key `key_fn`
+/// returns for them, in a single pass. A row whose key was already seen replaces the
+/// earlier row; see [`parse_result_set_indexed_unique`] to reject duplicates instead.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+/// * `key_fn` - Returns the key of a parsed row.
+///
+/// # Errors
+///
+/// Returns the error of the first row that could not be converted.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::ResultSet;
+/// use aws_athena_parser::{parse_result_set_indexed, FromAthena};
+/// use aws_athena_parser::{anyhow, HashMap};
+///
+/// #[derive(FromAthena)]
+/// struct Country {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// let result_set = ResultSet::builder().build();
+/// let countries = parse_result_set_indexed(result_set, |c: &Country| c.id).unwrap();
+/// assert!(countries.get(&1).is_none());
+/// ```
+pub fn parse_result_set_indexed<T, K>(
+    result_set: ResultSet,
+    key_fn: impl Fn(&T) -> K,
+) -> anyhow::Result<HashMap<K, T>>
+where
+    T: FromAthena,
+    K: Eq + std::hash::Hash,
+{
+    let options = ParserOptions::default();
+    let types = column_types(&result_set, &options);
+
+    map_rows_iter(&result_set, false, T::athena_positions(), &options)
+        .map(|row| {
+            let parsed = row.parse(&types)?;
+            Ok((key_fn(&parsed), parsed))
+        })
+        .collect()
+}
+
+/// Parses every row of the given ResultSet into `T`, keyed by `key_fn`, rejecting
+/// duplicate keys.
+///
+/// As [`parse_result_set_indexed`], except that two rows sharing a key are an error
+/// rather than the later row replacing the earlier one.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+/// * `key_fn` - Returns the key of a parsed row.
+///
+/// # Errors
+///
+/// Returns the error of the first row that could not be converted, or an error naming
+/// the first key returned for more than one row.
+pub fn parse_result_set_indexed_unique<T, K>(
+    result_set: ResultSet,
+    key_fn: impl Fn(&T) -> K,
+) -> anyhow::Result<HashMap<K, T>>
+where
+    T: FromAthena,
+    K: Eq + std::hash::Hash + std::fmt::Debug,
+{
+    let options = ParserOptions::default();
+    let types = column_types(&result_set, &options);
+    let mut indexed = HashMap::new();
+
+    for row in map_rows_iter(&result_set, false, T::athena_positions(), &options) {
+        let parsed = row.parse(&types)?;
+
+        match indexed.entry(key_fn(&parsed)) {
+            std::collections::hash_map::Entry::Occupied(entry) => {
+                return Err(anyhow::Error::msg(format!(
+                    "Duplicate key within result set. `{:?}` was found more than once!",
+                    entry.key()
+                )));
+            }
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(parsed);
+            }
+        }
+    }
+
+    Ok(indexed)
+}
+
+/// Parses the only row of the given ResultSet into `T`.
+///
+/// Intended for queries expected to return exactly one row, such as a lookup by
+/// primary key or an aggregate. Rows are mapped lazily, so the row is parsed without
+/// building a `Vec` of every row first.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the row to be parsed.
+///
+/// # Errors
+///
+/// Returns an error if the ResultSet holds no rows or more than one row, or if the row
+/// could not be converted.
+pub fn parse_single<T: FromAthena>(result_set: ResultSet) -> anyhow::Result<T> {
+    parse_single_with_options(result_set, &ParserOptions::default())
+}
+
+/// Parses the only row of the given ResultSet into `T` using the provided options.
+///
+/// As [`parse_single`], except that a leading header row is skipped, and not counted,
+/// when [`ParserOptions::skip_header`] is set.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the row to be parsed.
+/// * `options` - The [`ParserOptions`] controlling how the row is parsed.
+///
+/// # Errors
+///
+/// Returns an error if the ResultSet holds no rows or more than one row, or if the row
+/// could not be converted.
+pub fn parse_single_with_options<T: FromAthena>(
+    result_set: ResultSet,
+    options: &ParserOptions,
+) -> anyhow::Result<T> {
+    let mut rows = map_rows_iter(
+        &result_set,
+        options.skip_header,
+        T::athena_positions(),
+        options,
+    );
+
+    match (rows.next(), rows.next()) {
+        (Some(row), None) => {
+            let types = column_types(&result_set, options);
+            row.parse(&types)
+        }
+        (first, second) => Err(anyhow::Error::msg(format!(
+            "Expected exactly one row within result set, found {}!",
+            first.iter().chain(&second).count() + rows.count()
+        ))),
+    }
+}
+
+/// Parses the first row of the given ResultSet into `T`, if there is one.
+///
+/// Rows are mapped lazily, so rows after the first are neither mapped nor parsed.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+///
+/// # Returns
+///
+/// `None` if the ResultSet holds no rows, otherwise the first row.
+///
+/// # Errors
+///
+/// Returns an error if the first row could not be converted.
+pub fn parse_first<T: FromAthena>(result_set: ResultSet) -> anyhow::Result<Option<T>> {
+    parse_first_with_options(result_set, &ParserOptions::default())
+}
+
+/// Parses the first row of the given ResultSet into `T` using the provided options.
+///
+/// As [`parse_first`], except that a leading header row is skipped when
+/// [`ParserOptions::skip_header`] is set.
+///
+/// # Arguments
+///
+/// * `result_set` - A ResultSet containing the rows to be parsed.
+/// * `options` - The [`ParserOptions`] controlling how the row is parsed.
+///
+/// # Returns
+///
+/// `None` if the ResultSet holds no rows, otherwise the first row.
+///
+/// # Errors
+///
+/// Returns an error if the first row could not be converted.
+pub fn parse_first_with_options<T: FromAthena>(
+    result_set: ResultSet,
+    options: &ParserOptions,
+) -> anyhow::Result<Option<T>> {
+    let types = column_types(&result_set, options);
+
+    map_rows_iter(
+        &result_set,
+        options.skip_header,
+        T::athena_positions(),
+        options,
+    )
+    .next()
+    .map(|row| row.parse(&types))
+    .transpose()
+}
+
+/// A row parser with its output type erased, as returned by [`erased_parser`].
+pub type ErasedParser =
+    Box<dyn Fn(HashMap<String, String>) -> anyhow::Result<Box<dyn Any>> + Send + Sync>;
+
+/// Returns a boxed closure parsing a row into `T` with [`FromAthena::from_athena`], boxed as
+/// `dyn Any`.
+///
+/// This lets a registry hold parsers for different types side by side, such as one per
+/// query name. The caller gets the parsed value back with `downcast`.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::{erased_parser, ErasedParser, FromAthena, HashMap};
+///
+/// #[derive(FromAthena)]
+/// struct Count {
+///     total: i64,
+/// }
+///
+/// let registry = HashMap::from([("count", erased_parser::<Count>())]);
+///
+/// let row = HashMap::from([("total".to_string(), "3".to_string())]);
+/// let parsed = registry["count"](row).unwrap();
+/// assert_eq!(parsed.downcast::<Count>().unwrap().total, 3);
+/// ```
+pub fn erased_parser<T: FromAthena + 'static>() -> ErasedParser {
+    Box::new(|row| Ok(Box::new(T::from_athena(row)?) as Box<dyn Any>))
+}
+
+/// Builds a ResultSet holding the given rows, the inverse of [`parse_result_set`].
+///
+/// The ResultSet has the given column metadata and one row per item, with values in
+/// column order. A column the item has no value for becomes a SQL null.
+///
+/// # Arguments
+///
+/// * `rows` - The items to convert with [`ToAthena::to_athena`].
+/// * `columns` - The `(name, type)` pairs of the columns, in order.
+///
+/// # Returns
+///
+/// A ResultSet without a header row, as returned for a query after the first page.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::{parse_result_set, to_result_set, FromAthena, HashMap, ToAthena};
+/// use aws_athena_parser::anyhow;
+///
+/// #[derive(FromAthena, ToAthena)]
+/// struct MyStruct {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// let rows = vec![MyStruct { id: 1, name: "first".to_string() }];
+/// let result_set = to_result_set(&rows, &[("id", "bigint"), ("name", "varchar")]);
+///
+/// let parsed: Vec<MyStruct> = parse_result_set(result_set).unwrap();
+/// assert_eq!(parsed[0].name, "first");
+/// ```
+pub fn to_result_set<T: ToAthena>(rows: &[T], columns: &[(&str, &str)]) -> ResultSet {
+    let column_info = columns
+        .iter()
+        .map(|(name, ty)| {
+            ColumnInfo::builder()
+                .name(*name)
+                .r#type(*ty)
+                .build()
+                .expect("name and type are set")
+        })
+        .collect();
+    let metadata = ResultSetMetadata::builder()
+        .set_column_info(Some(column_info))
+        .build();
+
+    let rows = rows
+        .iter()
+        .map(|item| {
+            let mut values = item.to_athena();
+            let data = columns
+                .iter()
+                .map(|(name, _)| {
+                    Datum::builder()
+                        .set_var_char_value(values.remove(*name))
+                        .build()
+                })
+                .collect();
+            Row::builder().set_data(Some(data)).build()
+        })
+        .collect();
+
+    ResultSet::builder()
+        .result_set_metadata(metadata)
+        .set_rows(Some(rows))
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::result_set;
+    use aws_sdk_athena::types::Datum;
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct Testing {
+        pub test: i64,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    #[allow(dead_code)]
+    struct BadTesting {
+        pub no_exist: String,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct LargeStruct {
+        pub test1: i64,
+        pub test2: i32,
+        pub test3: String,
+        pub test4: String,
+        pub test5: f64,
+        pub test6: bool,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct CollectionTesting {
+        pub list: Vec<i64>,
+        pub hash_set: std::collections::HashSet<String>,
+        pub btree_set: std::collections::BTreeSet<i32>,
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct BigDecimalTesting {
+        pub amount: bigdecimal::BigDecimal,
+    }
+
+    #[cfg(feature = "uuid")]
+    #[derive(from_athena_derive::FromAthena)]
+    struct UuidTesting {
+        pub id: uuid::Uuid,
+    }
+
+    #[cfg(feature = "chrono")]
+    #[derive(from_athena_derive::FromAthena)]
+    struct EpochTesting {
+        #[athena(epoch = "seconds")]
+        pub created: chrono::DateTime<chrono::Utc>,
+        #[athena(epoch = "millis")]
+        pub updated: chrono::DateTime<chrono::Utc>,
+    }
+
+    #[cfg(feature = "geo")]
+    #[derive(from_athena_derive::FromAthena)]
+    struct WktTesting {
+        #[athena(wkt)]
+        pub location: geo_types::Geometry,
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[derive(from_athena_derive::FromAthena)]
+    struct ZonedTesting {
+        pub created: chrono::DateTime<chrono_tz::Tz>,
+    }
+
+    #[cfg(feature = "system-time")]
+    #[derive(from_athena_derive::FromAthena)]
+    struct SystemTimeTesting {
+        pub created: std::time::SystemTime,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct BoolFromIntTesting {
+        #[athena(bool_from_int)]
+        pub flag: bool,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct SubsetTesting {
+        pub id: i64,
+        pub name: String,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct BinaryTesting {
+        pub data: Vec<u8>,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct OptionTesting {
+        pub test: Option<i64>,
+        pub nothing: Option<String>,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct PositionTesting {
+        pub name: String,
+        #[athena(position = 1)]
+        pub max_score: i64,
+        #[athena(position = 2)]
+        pub min_score: i64,
+    }
+
+    #[derive(from_athena_derive::FromAthena, Default)]
+    #[athena(default_on_empty_row)]
+    struct DefaultTesting {
+        pub test: i64,
+        pub name: String,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct TrimTesting {
+        #[athena(trim_matches = "\"")]
+        pub name: String,
+        #[athena(trim_matches = "\"'")]
+        pub test: i64,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Money {
+        cents: i64,
+    }
+
+    mod money {
+        pub fn from_athena_field(value: &str) -> anyhow::Result<super::Money> {
+            let (dollars, cents) = value
+                .trim_start_matches('$')
+                .split_once('.')
+                .unwrap_or((value, "0"));
+            Ok(super::Money {
+                cents: dollars.parse::<i64>()? * 100 + cents.parse::<i64>()?,
+            })
+        }
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct WithTesting {
+        #[athena(with = "money")]
+        pub price: Money,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct TupleTesting {
+        pub point: (i64, i64),
+        #[athena(delimiter = "|")]
+        pub labelled: (String, f64),
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct FlattenTesting {
+        pub test: i64,
+        #[athena(flatten)]
+        pub rest: HashMap<String, String>,
+    }
+
+    #[cfg(feature = "json")]
+    #[derive(from_athena_derive::FromAthena)]
+    struct FlattenJsonTesting {
+        pub test: i64,
+        #[athena(flatten)]
+        pub rest: serde_json::Map<String, serde_json::Value>,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct WideTesting {
+        pub signed: i128,
+        pub unsigned: u128,
+    }
+
+    #[derive(
+        from_athena_derive::FromAthena, from_athena_derive::ToAthena, Debug, Clone, PartialEq,
+    )]
+    struct RoundTripTesting {
+        pub id: i64,
+        pub name: Option<String>,
+        pub tags: Vec<String>,
+        #[athena(percent = "fraction")]
+        pub share: f64,
+        #[athena(bool_from_int)]
+        pub active: bool,
+        pub point: (i64, i64),
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct RenameTesting {
+        #[athena(rename = "user-id")]
+        pub user_id: i64,
+        pub note: Option<String>,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct PathTesting {
+        pub key: std::path::PathBuf,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    #[athena(lenient)]
+    struct LenientTesting {
+        pub id: i64,
+        pub count: u32,
+        pub name: String,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct SplitTesting {
+        pub id: i64,
+        #[athena(flatten_delimited = "full_name", delimiter = ", ")]
+        pub last: String,
+        #[athena(flatten_delimited = "full_name")]
+        pub first: String,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct LocaleTesting {
+        #[athena(decimal_separator = ',')]
+        pub ratio: f64,
+        #[athena(decimal_separator = ',', thousands_separator = '.')]
+        pub total: f32,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct NullElementTesting {
+        pub values: Vec<Option<i64>>,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct RawIdentTesting {
+        pub r#type: String,
+    }
+
+    #[cfg(feature = "chrono")]
+    #[derive(from_athena_derive::FromAthena)]
+    struct FormatsTesting {
+        #[athena(formats = ["%Y-%m-%d", "%m/%d/%Y"])]
+        pub day: chrono::NaiveDate,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct BytesEncodingTesting {
+        #[athena(bytes_encoding = "base64")]
+        pub base64: Vec<u8>,
+        #[athena(bytes_encoding = "hex")]
+        pub hex: Vec<u8>,
+        #[athena(bytes_encoding = "utf8")]
+        pub utf8: Vec<u8>,
+        pub default: Vec<u8>,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct DurationTesting {
+        pub elapsed: std::time::Duration,
+        #[athena(duration_unit = "millis")]
+        pub latency: std::time::Duration,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct CollectPrefixTesting {
+        pub id: i64,
+        #[athena(collect_prefix = "score_")]
+        pub scores: Vec<f64>,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct PrefixMapTesting {
+        pub id: i64,
+        #[athena(prefix = "metric_")]
+        pub metrics: HashMap<String, f64>,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    #[serde(rename_all = "camelCase")]
+    struct SerdeRenameTesting {
+        pub user_id: i64,
+        #[serde(rename = "display-name", default)]
+        pub name: String,
+        #[serde(rename(serialize = "mail", deserialize = "email_address"))]
+        pub email: String,
+        #[serde(rename = "ignored")]
+        #[athena(rename = "created")]
+        pub created_at: i64,
+    }
+
+    #[derive(Debug, PartialEq, from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    enum Permission {
+        #[athena(rename = "READ")]
+        Read,
+        #[athena(rename = "WRITE")]
+        Write,
+        #[athena(rename = "EXEC")]
+        Exec,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct PermissionTesting {
+        pub permissions: Vec<Permission>,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct NamePart {
+        pub id: i64,
+        pub name: String,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct ScorePart {
+        pub id: i64,
+        pub score: f64,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct ComposedTesting {
+        #[athena(flatten)]
+        pub name: NamePart,
+        #[athena(flatten)]
+        pub score: ScorePart,
+        #[athena(flatten)]
+        pub rest: HashMap<String, String>,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct SignTesting {
+        pub count: i64,
+        pub score: f64,
+        #[athena(normalize_zero)]
+        pub normalized: Option<f32>,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct RangeTesting {
+        #[athena(min = 0, max = 150)]
+        pub age: i32,
+        #[athena(min = -1.5)]
+        pub score: Option<f64>,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct CharTesting {
+        pub symbol: char,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct DelimiterTesting {
+        #[athena(array_delim = ";")]
+        pub list: Vec<i64>,
+        pub counts: std::collections::BTreeMap<String, i64>,
+        #[athena(map_entry_delim = ";", map_kv_delim = ":")]
+        pub custom: HashMap<String, String>,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct HexTesting {
+        #[athena(hex)]
+        pub data: Vec<u8>,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct ReversedTesting {
+        pub test3: String,
+        pub test2: i32,
+        pub test1: i64,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    #[athena(error_prefix = "orders")]
+    #[allow(dead_code)]
+    struct PrefixTesting {
+        pub no_exist: String,
+    }
+
+    #[derive(from_athena_derive::FromAthena)]
+    struct EventTesting {
+        pub event: String,
+    }
+
+    #[derive(from_athena_derive::FromAthena, from_athena_derive::ToAthena)]
+    struct PercentTesting {
+        #[athena(percent)]
+        pub fraction: f64,
+        #[athena(percent = "fraction")]
+        pub explicit_fraction: f64,
+        #[athena(percent = "raw")]
+        pub raw: f64,
+    }
+
+    #[test]
+    fn convert_result_set_to_map() {
+        let result_set = result_set(&[("test", "bigint")], &[&["100"]]);
+
+        let res = build_map(result_set);
+        assert!(res.len() == 1);
+        assert!(res[0].contains_key("test"));
+        assert_eq!(res[0].get("test").unwrap(), "100");
+    }
+
+    #[test]
+    fn error_try_build_map_without_metadata() {
+        let rows = result_set(&[("test", "bigint")], &[&["100"], &["200"]]);
+        assert_eq!(try_build_map(rows.clone()).unwrap().len(), 2);
+
+        let no_metadata = ResultSet::builder()
+            .set_rows(Some(rows.rows().to_vec()))
+            .build();
+        assert!(build_map(no_metadata.clone()).is_empty());
+        assert_eq!(
+            try_build_map(no_metadata).unwrap_err().to_string(),
+            "Missing metadata within result set. 2 rows were returned without column information!"
+        );
+
+        assert!(try_build_map(ResultSet::builder().build())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn convert_result_set_to_borrowed_map() {
+        let result_set = result_set(
+            &[
+                ("test", "bigint"),
+                ("name", "varchar"),
+                ("nothing", "unknown"),
+            ],
+            &[&["100", "first", ""], &["200", "second", ""]],
+        );
+
+        let borrowed = build_map_borrowed(&result_set);
+        let owned = build_map(result_set.clone());
+        assert_eq!(borrowed.len(), owned.len());
+        for (borrowed, owned) in borrowed.iter().zip(owned.iter()) {
+            assert_eq!(borrowed.len(), owned.len());
+            assert!(owned
+                .iter()
+                .all(|(column, value)| borrowed[column.as_str()] == value));
+        }
+
+        let source = result_set.rows()[1].data()[1].var_char_value().unwrap();
+        assert!(std::ptr::eq(borrowed[1]["name"], source));
+
+        let res = Testing::from_athena_source(&borrowed[0], &HashMap::new()).unwrap();
+        assert_eq!(res.test, 100);
+    }
+
+    #[test]
+    fn parse_result_set_into_raw_rows() {
+        let result_set = result_set(
+            &[("test", "bigint"), ("name", "varchar")],
+            &[&["100", "first"]],
+        );
+
+        let res: Vec<HashMap<String, String>> = parse_result_set(result_set.clone()).unwrap();
+        assert_eq!(res, build_map(result_set));
+        assert_eq!(res[0]["name"], "first");
+    }
+
+    #[test]
+    fn convert_row_to_map() {
+        let row = Row::builder()
+            .data(Datum::builder().var_char_value("100").build())
+            .data(Datum::builder().build())
+            .build();
+        let columns = ["test".to_string(), "note".to_string()];
+
+        let res = row_to_map(&row, &columns);
+        assert_eq!(
+            res,
+            HashMap::from([("test".to_string(), "100".to_string())])
+        );
+    }
+
+    #[test]
+    fn converted_results_to_struct() {
+        let result_set = result_set(&[("test", "bigint")], &[&["100"]]);
+
+        let res: Vec<Testing> = build_map(result_set)
+            .iter()
+            .flat_map(|x| Testing::from_athena(x.clone()))
+            .collect();
+
+        assert_eq!(res[0].test, 100);
+    }
+
+    #[test]
+    fn converted_results_to_large_struct() {
+        let result_set = result_set(
+            &[
+                ("test1", "bigint"),
+                ("test2", "integer"),
+                ("test3", "varchar"),
+                ("test4", "varchar"),
+                ("test5", "double"),
+                ("test6", "boolean"),
+            ],
+            &[&["1000", "100", "test", "test", "100.0", "true"]],
+        );
+
+        let res: Vec<Result<LargeStruct, anyhow::Error>> = build_map(result_set)
+            .iter()
+            .map(|x| LargeStruct::from_athena(x.clone()))
+            .collect();
+
+        for res in res {
+            match res {
+                Ok(r) => {
+                    assert_eq!(r.test1, 1000);
+                    assert_eq!(r.test2, 100);
+                    assert_eq!(r.test3, "test".to_string());
                     assert_eq!(r.test4, "test");
                     assert_eq!(r.test5, 100.0);
                     assert!(r.test6);
@@ -218,31 +1869,1562 @@ mod test {
     }
 
     #[test]
-    fn error_convert_results_to_invalid_struct() {
-        let column = ColumnInfo::builder()
-            .name("test")
-            .r#type("bigint")
-            .build()
-            .unwrap();
-        let metadata = ResultSetMetadata::builder().column_info(column).build();
-        let data = Datum::builder()
-            .set_var_char_value(Some("100".to_string()))
-            .build();
-        let row = Row::builder().set_data(Some(vec![data])).build();
-        let result_set = ResultSet::builder()
-            .result_set_metadata(metadata)
-            .set_rows(Some(vec![row]))
-            .build();
+    fn error_convert_results_to_invalid_struct() {
+        let result_set = result_set(&[("test", "bigint")], &[&["100"]]);
+
+        let res: Vec<Result<BadTesting, anyhow::Error>> = build_map(result_set)
+            .iter()
+            .map(|x| BadTesting::from_athena(x.clone()))
+            .collect();
+
+        assert!(res[0].is_err());
+        assert_eq!(
+            res[0].as_ref().err().map(|e| format!("{:#}", e)).unwrap(),
+            "BadTesting: Missing field within result set. `no_exist` was not found!".to_string()
+        );
+    }
+
+    #[test]
+    fn converted_percentages() {
+        let row = HashMap::from([
+            ("fraction".to_string(), "98.5%".to_string()),
+            ("explicit_fraction".to_string(), "42%".to_string()),
+            ("raw".to_string(), "98.5%".to_string()),
+        ]);
+
+        let res = PercentTesting::from_athena(row.clone()).unwrap();
+        assert_eq!(res.fraction, 0.985);
+        assert_eq!(res.explicit_fraction, 0.42);
+        assert_eq!(res.raw, 98.5);
+
+        assert_eq!(res.to_athena(), row);
+    }
+
+    #[test]
+    fn format_percentages() {
+        assert_eq!(field::format_percent(0.985, true), "98.5%");
+        assert_eq!(field::format_percent(0.5, true), "50%");
+        assert_eq!(field::format_percent(1.0, true), "100%");
+        assert_eq!(field::format_percent(12.3456, true), "1234.56%");
+        assert_eq!(field::format_percent(-0.0012, true), "-0.12%");
+        assert_eq!(field::format_percent(0.0, true), "0%");
+        assert_eq!(field::format_percent(98.5, false), "98.5%");
+    }
+
+    #[test]
+    fn error_convert_malformed_percentage() {
+        let row = HashMap::from([
+            ("fraction".to_string(), "42%".to_string()),
+            ("explicit_fraction".to_string(), "42%".to_string()),
+            ("raw".to_string(), "abc%".to_string()),
+        ]);
+
+        let res = PercentTesting::from_athena(row);
+        assert!(res.is_err());
+        assert_eq!(
+            res.err().map(|e| format!("{:#}", e)).unwrap(),
+            "PercentTesting: Invalid percentage within result set. `raw` could not parse `abc%`!"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn error_includes_row_when_enabled() {
+        let long = "x".repeat(100);
+        let result_set = result_set(
+            &[("test", "bigint"), ("note", "varchar")],
+            &[&["abc", long.as_str()]],
+        );
+
+        let options = ParserOptions::new().include_row_in_error(true);
+        let res = parse_result_set_with_options::<Testing>(result_set.clone(), &options);
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            format!(
+                "Failed to parse row 0: {{note: {}..., test: abc}}",
+                "x".repeat(64)
+            )
+        );
+
+        let res = parse_result_set_with_options::<Testing>(result_set, &ParserOptions::new());
+        assert_eq!(
+            res.err().map(|e| format!("{:#}", e)).unwrap(),
+            "Testing: Invalid integer within result set. `test` could not parse `abc`: invalid digit!"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn missing_columns_for_partial_row() {
+        let row = HashMap::from([
+            ("test1".to_string(), "1000".to_string()),
+            ("test2".to_string(), "100".to_string()),
+            ("test3".to_string(), "test".to_string()),
+            ("test5".to_string(), "100.0".to_string()),
+            ("test6".to_string(), "true".to_string()),
+        ]);
+
+        assert_eq!(missing_columns::<LargeStruct>(&row), vec!["test4"]);
+    }
+
+    #[test]
+    fn converted_arrays_to_collections() {
+        let row = HashMap::from([
+            ("list".to_string(), "[3, 1, 3]".to_string()),
+            ("hash_set".to_string(), "[a, b, a]".to_string()),
+            ("btree_set".to_string(), "3,1,3,2".to_string()),
+        ]);
+
+        let res = CollectionTesting::from_athena(row).unwrap();
+        assert_eq!(res.list, vec![3, 1, 3]);
+        assert_eq!(res.hash_set.len(), 2);
+        assert!(res.hash_set.contains("a") && res.hash_set.contains("b"));
+        assert_eq!(res.btree_set.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn converted_empty_array_to_collections() {
+        let row = HashMap::from([
+            ("list".to_string(), "[]".to_string()),
+            ("hash_set".to_string(), "[]".to_string()),
+            ("btree_set".to_string(), "".to_string()),
+        ]);
+
+        let res = CollectionTesting::from_athena(row).unwrap();
+        assert!(res.list.is_empty());
+        assert!(res.hash_set.is_empty());
+        assert!(res.btree_set.is_empty());
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn converted_uuid() {
+        let row = HashMap::from([(
+            "id".to_string(),
+            "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string(),
+        )]);
+
+        let res = UuidTesting::from_athena(row).unwrap();
+        assert_eq!(res.id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    }
+
+    #[test]
+    fn converted_types_sharing_known_names() {
+        mod custom {
+            use crate::{FromAthena, HashMap};
+
+            /// Not `uuid::Uuid`, so it is parsed with its own `FromStr`.
+            #[derive(Debug, PartialEq)]
+            pub struct Uuid(pub String);
+
+            impl std::str::FromStr for Uuid {
+                type Err = std::convert::Infallible;
+
+                fn from_str(value: &str) -> Result<Self, Self::Err> {
+                    Ok(Uuid(value.to_uppercase()))
+                }
+            }
+
+            /// Not `serde_json::Map`, so a flatten field reads it with `FromAthena`.
+            #[derive(from_athena_derive::FromAthena)]
+            pub struct Map {
+                pub name: String,
+            }
+        }
+
+        #[derive(from_athena_derive::FromAthena)]
+        struct Custom {
+            id: custom::Uuid,
+            #[athena(flatten)]
+            rest: custom::Map,
+        }
+
+        let row = HashMap::from([
+            ("id".to_string(), "user-1".to_string()),
+            ("name".to_string(), "first".to_string()),
+        ]);
+        let res = Custom::from_athena(row).unwrap();
+        assert_eq!(res.id, custom::Uuid("USER-1".to_string()));
+        assert_eq!(res.rest.name, "first");
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn error_convert_malformed_uuid() {
+        let row = HashMap::from([("id".to_string(), "not-a-uuid".to_string())]);
+
+        let res = UuidTesting::from_athena(row);
+        assert!(res.err().map(|e| format!("{:#}", e)).unwrap().starts_with(
+            "UuidTesting: Invalid UUID within result set. `id` could not parse `not-a-uuid`"
+        ));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn converted_epoch() {
+        let row = HashMap::from([
+            ("created".to_string(), "1706704200".to_string()),
+            ("updated".to_string(), "1706704200123".to_string()),
+        ]);
+
+        let res = EpochTesting::from_athena(row).unwrap();
+        assert_eq!(res.created.to_rfc3339(), "2024-01-31T12:30:00+00:00");
+        assert_eq!(res.updated.timestamp_millis(), 1_706_704_200_123);
+    }
+
+    #[cfg(feature = "geo")]
+    #[test]
+    fn converted_wkt() {
+        let row = HashMap::from([("location".to_string(), "LINESTRING (0 0, 1 1)".to_string())]);
+
+        let res = WktTesting::from_athena(row).unwrap();
+        assert!(matches!(res.location, geo_types::Geometry::LineString(_)));
+
+        let row = HashMap::from([("location".to_string(), "not wkt".to_string())]);
+        assert!(WktTesting::from_athena(row)
+            .err()
+            .map(|e| format!("{:#}", e))
+            .unwrap()
+            .starts_with("WktTesting: Invalid WKT within result set. `location`"));
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn converted_zoned() {
+        let row = HashMap::from([(
+            "created".to_string(),
+            "2024-07-01 09:00:00.000 Europe/London".to_string(),
+        )]);
+
+        let res = ZonedTesting::from_athena(row).unwrap();
+        assert_eq!(res.created.timezone(), chrono_tz::Europe::London);
+        assert_eq!(res.created.to_rfc3339(), "2024-07-01T09:00:00+01:00");
+    }
+
+    #[cfg(feature = "system-time")]
+    #[test]
+    fn converted_system_time() {
+        let row = HashMap::from([("created".to_string(), "2024-01-31 12:30:00.000".to_string())]);
+
+        let res = SystemTimeTesting::from_athena(row).unwrap();
+        assert_eq!(
+            res.created,
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_706_704_200)
+        );
+    }
+
+    #[test]
+    fn converted_bool_from_int() {
+        let parse = |v: &str| {
+            BoolFromIntTesting::from_athena(HashMap::from([("flag".to_string(), v.to_string())]))
+        };
+
+        assert!(!parse("0").unwrap().flag);
+        assert!(parse("1").unwrap().flag);
+        assert_eq!(
+            parse("2").err().map(|e| format!("{:#}", e)).unwrap(),
+            "BoolFromIntTesting: Invalid boolean within result set. `flag` could not parse `2`, expected `0` or `1`!"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn parse_result_set_ignores_unmapped_columns() {
+        let result_set = result_set(
+            &[
+                ("id", "bigint"),
+                ("score", "double"),
+                ("name", "varchar"),
+                ("active", "boolean"),
+                ("created", "timestamp"),
+            ],
+            &[&["7", "not a number", "test", "maybe", "garbage"]],
+        );
+
+        let res: Vec<SubsetTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].id, 7);
+        assert_eq!(res[0].name, "test");
+    }
+
+    #[test]
+    fn converted_binary() {
+        let row = HashMap::from([("data".to_string(), "de ad be ef".to_string())]);
+
+        let res = BinaryTesting::from_athena(row).unwrap();
+        assert_eq!(res.data, vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn converted_hex() {
+        let row = HashMap::from([("data".to_string(), "deadbeef".to_string())]);
+        let res = HexTesting::from_athena(row).unwrap();
+        assert_eq!(res.data, vec![0xde, 0xad, 0xbe, 0xef]);
+
+        // Valid base64, but not a whole number of hex pairs.
+        let row = HashMap::from([("data".to_string(), "abc".to_string())]);
+        assert!(HexTesting::from_athena(row).is_err());
+    }
+
+    #[test]
+    fn converted_results_to_reversed_struct() {
+        let result_set = result_set(
+            &[
+                ("test1", "bigint"),
+                ("test2", "integer"),
+                ("test3", "varchar"),
+            ],
+            &[&["1000", "100", "test"]],
+        );
+
+        let res: Vec<ReversedTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(res[0].test1, 1000);
+        assert_eq!(res[0].test2, 100);
+        assert_eq!(res[0].test3, "test");
+    }
+
+    #[test]
+    fn parse_result_set_skips_header_row() {
+        #[derive(from_athena_derive::FromAthena)]
+        struct Named {
+            name: String,
+        }
+
+        let result_set = result_set(&[("name", "varchar")], &[&["name"], &["first"]]);
+
+        let res: Vec<Named> = parse_result_set(result_set.clone()).unwrap();
+        assert_eq!(
+            res.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(),
+            vec!["name", "first"]
+        );
+
+        let options = ParserOptions::new().skip_header(true);
+        let res: Vec<Named> = parse_result_set_with_options(result_set, &options).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].name, "first");
+    }
+
+    #[test]
+    fn error_includes_configured_prefix() {
+        let res = PrefixTesting::from_athena(HashMap::new());
+        assert_eq!(
+            res.err().map(|e| format!("{:#}", e)).unwrap(),
+            "orders: Missing field within result set. `no_exist` was not found!".to_string()
+        );
+    }
+
+    #[test]
+    fn parse_result_set_grouped_by_key() {
+        let result_set = result_set(
+            &[("user_id", "varchar"), ("event", "varchar")],
+            &[&["a", "login"], &["b", "login"], &["a", "logout"]],
+        );
+
+        let res: HashMap<String, Vec<EventTesting>> =
+            parse_result_set_grouped(result_set.clone(), "user_id").unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(
+            res["a"]
+                .iter()
+                .map(|e| e.event.as_str())
+                .collect::<Vec<_>>(),
+            vec!["login", "logout"]
+        );
+        assert_eq!(res["b"].len(), 1);
+
+        let res = parse_result_set_grouped::<EventTesting>(result_set, "session_id");
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            "Missing key column within result set. `session_id` was not found!".to_string()
+        );
+    }
+
+    #[test]
+    fn parse_single_row() {
+        let empty = result_set(&[("test", "bigint")], &[]);
+        assert_eq!(
+            parse_single::<Testing>(empty).err().unwrap().to_string(),
+            "Expected exactly one row within result set, found 0!"
+        );
+
+        let one = result_set(&[("test", "bigint")], &[&["100"]]);
+        assert_eq!(parse_single::<Testing>(one).unwrap().test, 100);
+
+        let many = result_set(&[("test", "bigint")], &[&["100"], &["200"]]);
+        assert_eq!(
+            parse_single::<Testing>(many).err().unwrap().to_string(),
+            "Expected exactly one row within result set, found 2!"
+        );
+
+        let more = result_set(&[("test", "bigint")], &[&["100"], &["200"], &["300"]]);
+        assert_eq!(
+            parse_single::<Testing>(more).err().unwrap().to_string(),
+            "Expected exactly one row within result set, found 3!"
+        );
+    }
+
+    #[test]
+    fn parse_first_row() {
+        let empty = result_set(&[("test", "bigint")], &[]);
+        assert!(parse_first::<Testing>(empty).unwrap().is_none());
+
+        let one = result_set(&[("test", "bigint")], &[&["100"]]);
+        assert_eq!(parse_first::<Testing>(one).unwrap().unwrap().test, 100);
+
+        let many = result_set(&[("test", "bigint")], &[&["100"], &["not a number"]]);
+        assert_eq!(parse_first::<Testing>(many).unwrap().unwrap().test, 100);
+    }
+
+    #[test]
+    fn parse_single_and_first_skip_header() {
+        let options = ParserOptions::new().skip_header(true);
+        let with_header = || result_set(&[("test", "bigint")], &[&["test"], &["100"]]);
+
+        assert_eq!(
+            parse_single_with_options::<Testing>(with_header(), &options)
+                .unwrap()
+                .test,
+            100
+        );
+        assert!(parse_single::<Testing>(with_header()).is_err());
+
+        let res = parse_first_with_options::<Testing>(with_header(), &options).unwrap();
+        assert_eq!(res.unwrap().test, 100);
+        assert!(parse_first::<Testing>(with_header()).is_err());
+
+        let header_only = result_set(&[("test", "bigint")], &[&["test"]]);
+        assert!(parse_first_with_options::<Testing>(header_only, &options)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn unknown_typed_column_is_none() {
+        let result_set = result_set(
+            &[("test", "bigint"), ("nothing", "unknown")],
+            &[&["100", ""]],
+        );
+
+        assert!(!build_map(result_set.clone())[0].contains_key("nothing"));
+
+        let res: Vec<OptionTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(res[0].test, Some(100));
+        assert_eq!(res[0].nothing, None);
+    }
+
+    #[test]
+    fn null_value_is_none() {
+        let result_set = crate::test_util::result_set_with_nulls(
+            &[("test", "bigint"), ("nothing", "varchar")],
+            &[&[None, Some("")], &[Some("100"), None]],
+        );
+
+        assert!(!build_map(result_set.clone())[0].contains_key("test"));
+
+        let res: Vec<OptionTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(res[0].test, None);
+        assert_eq!(res[0].nothing, Some(String::new()));
+        assert_eq!(res[1].test, Some(100));
+        assert_eq!(res[1].nothing, None);
+    }
+
+    #[test]
+    fn parse_result_set_by_position() {
+        let result_set = result_set(
+            &[
+                ("name", "varchar"),
+                ("_col1", "bigint"),
+                ("_col2", "bigint"),
+            ],
+            &[&["test", "100", "5"]],
+        );
+
+        let res: Vec<PositionTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(res[0].name, "test");
+        assert_eq!(res[0].max_score, 100);
+        assert_eq!(res[0].min_score, 5);
+    }
+
+    #[test]
+    fn parse_result_set_by_position_with_shared_names() {
+        let result_set = result_set(
+            &[
+                ("name", "varchar"),
+                ("score", "bigint"),
+                ("score", "bigint"),
+                ("max_score", "bigint"),
+            ],
+            &[&["test", "100", "5", "7"]],
+        );
+
+        let res: Vec<PositionTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(res[0].max_score, 100);
+        assert_eq!(res[0].min_score, 5);
+
+        // Without a ResultSet, the fields are read by name.
+        let row = HashMap::from([
+            ("name".to_string(), "test".to_string()),
+            ("max_score".to_string(), "7".to_string()),
+            ("min_score".to_string(), "1".to_string()),
+        ]);
+        let res = PositionTesting::from_athena(row).unwrap();
+        assert_eq!(res.max_score, 7);
+        assert_eq!(res.min_score, 1);
+    }
+
+    #[test]
+    fn default_on_empty_row() {
+        let result_set = result_set(
+            &[("id", "bigint"), ("test", "bigint"), ("name", "varchar")],
+            &[&["1", "", ""], &["2", "100", "test"]],
+        );
+
+        let res: Vec<DefaultTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(res[0].test, 0);
+        assert_eq!(res[0].name, "");
+        assert_eq!(res[1].test, 100);
+        assert_eq!(res[1].name, "test");
+
+        let partial = HashMap::from([("test".to_string(), "".to_string())]);
+        assert!(DefaultTesting::from_athena(partial).is_ok());
+
+        let partial = HashMap::from([("name".to_string(), "test".to_string())]);
+        assert_eq!(
+            DefaultTesting::from_athena(partial)
+                .err()
+                .map(|e| format!("{:#}", e))
+                .unwrap(),
+            "DefaultTesting: Missing field within result set. `test` was not found!"
+        );
+    }
+
+    #[test]
+    fn unquote_column_names() {
+        let result_set = result_set(
+            &[("\"quoted col\"", "varchar"), ("\"test\"", "bigint")],
+            &[&["a", "100"]],
+        );
+
+        let quoted = build_map(result_set.clone());
+        assert!(quoted[0].contains_key("\"quoted col\""));
+
+        let options = ParserOptions::new().unquote_column_names(true);
+        let unquoted = build_map_with_options(result_set.clone(), &options);
+        assert_eq!(unquoted[0]["quoted col"], "a");
+
+        let res: Vec<Testing> = parse_result_set_with_options(result_set, &options).unwrap();
+        assert_eq!(res[0].test, 100);
+    }
+
+    #[test]
+    fn key_by_column_label() {
+        let column = ColumnInfo::builder()
+            .name("_col0")
+            .label("test")
+            .r#type("bigint")
+            .build()
+            .unwrap();
+        let result_set = ResultSet::builder()
+            .result_set_metadata(ResultSetMetadata::builder().column_info(column).build())
+            .rows(
+                Row::builder()
+                    .data(Datum::builder().var_char_value("100").build())
+                    .build(),
+            )
+            .build();
+
+        assert!(build_map(result_set.clone())[0].contains_key("_col0"));
+        assert!(parse_result_set::<Testing>(result_set.clone()).is_err());
+
+        let options = ParserOptions::new().key_by_label(true);
+        let res: Vec<Testing> = parse_result_set_with_options(result_set, &options).unwrap();
+        assert_eq!(res[0].test, 100);
+    }
+
+    #[test]
+    fn converted_trim_matches() {
+        let parse = |name: &str, test: &str| {
+            TrimTesting::from_athena(HashMap::from([
+                ("name".to_string(), name.to_string()),
+                ("test".to_string(), test.to_string()),
+            ]))
+            .unwrap()
+        };
+
+        let res = parse("\"value\"", "'100'");
+        assert_eq!(res.name, "value");
+        assert_eq!(res.test, 100);
+
+        // Each end is stripped on its own, so a quote on only one side is stripped too.
+        let res = parse("\"value", "\"100'");
+        assert_eq!(res.name, "value");
+        assert_eq!(res.test, 100);
+
+        let res = parse("va\"lue", "100");
+        assert_eq!(res.name, "va\"lue");
+    }
+
+    #[test]
+    fn converted_with_module() {
+        let row = HashMap::from([("price".to_string(), "$12.34".to_string())]);
+        let res = WithTesting::from_athena(row).unwrap();
+        assert_eq!(res.price, Money { cents: 1234 });
+
+        let row = HashMap::from([("price".to_string(), "$12.ab".to_string())]);
+        assert_eq!(
+            WithTesting::from_athena(row)
+                .err()
+                .map(|e| format!("{:#}", e))
+                .unwrap(),
+            "WithTesting: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn error_convert_overflowing_integer() {
+        let row = HashMap::from([("test".to_string(), "99999999999999999999".to_string())]);
+
+        let res = Testing::from_athena(row);
+        assert_eq!(
+            res.err().map(|e| format!("{:#}", e)).unwrap(),
+            "Testing: Invalid integer within result set. `test` could not parse `99999999999999999999`: overflow, the value is too large for the field type!"
+        );
+    }
+
+    #[test]
+    fn build_map_into_reuses_buffer() {
+        let first = result_set(&[("test", "bigint")], &[&["1"], &["2"], &["3"]]);
+        let second = result_set(&[("other", "bigint")], &[&["4"]]);
+        let mut buffer = Vec::new();
+
+        build_map_into(&first, &mut buffer);
+        assert_eq!(buffer, build_map(first));
+        let capacity = buffer.capacity();
+
+        build_map_into(&second, &mut buffer);
+        assert_eq!(
+            buffer,
+            vec![HashMap::from([("other".to_string(), "4".to_string())])]
+        );
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn converted_tuple() {
+        let parse = |point: &str| {
+            TupleTesting::from_athena(HashMap::from([
+                ("point".to_string(), point.to_string()),
+                ("labelled".to_string(), "a | 1.5".to_string()),
+            ]))
+        };
+
+        let res = parse("12,34").unwrap();
+        assert_eq!(res.point, (12, 34));
+        assert_eq!(res.labelled, ("a".to_string(), 1.5));
+
+        assert_eq!(
+            parse("12,34,56").err().map(|e| format!("{:#}", e)).unwrap(),
+            "TupleTesting: Invalid tuple within result set. `point` expected 2 elements but `12,34,56` has 3!"
+        );
+        assert_eq!(
+            parse("12,x").err().map(|e| format!("{:#}", e)).unwrap(),
+            "TupleTesting: Invalid integer within result set. `point` could not parse `x`: invalid digit!"
+        );
+    }
+
+    #[test]
+    fn converted_flatten() {
+        let result_set = result_set(
+            &[
+                ("test", "bigint"),
+                ("count", "integer"),
+                ("note", "varchar"),
+            ],
+            &[&["100", "5", "x"]],
+        );
+
+        let res: Vec<FlattenTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(res[0].test, 100);
+        assert_eq!(
+            res[0].rest,
+            HashMap::from([
+                ("count".to_string(), "5".to_string()),
+                ("note".to_string(), "x".to_string()),
+            ])
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn converted_flatten_json() {
+        let result_set = result_set(
+            &[
+                ("test", "bigint"),
+                ("count", "integer"),
+                ("active", "boolean"),
+                ("code", "varchar"),
+            ],
+            &[&["100", "5", "true", "007"]],
+        );
+
+        let res: Vec<FlattenJsonTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(res[0].test, 100);
+        assert_eq!(
+            serde_json::Value::Object(res[0].rest.clone()),
+            serde_json::json!({"count": 5, "active": true, "code": "007"})
+        );
+    }
+
+    #[test]
+    fn from_athena_with_raw_returns_row() {
+        let row = HashMap::from([
+            ("test".to_string(), "100".to_string()),
+            ("extra".to_string(), "kept".to_string()),
+        ]);
+
+        let (res, raw) = Testing::from_athena_with_raw(row.clone()).unwrap();
+        assert_eq!(res.test, 100);
+        assert_eq!(raw, row);
+
+        let row = HashMap::from([("test".to_string(), "abc".to_string())]);
+        assert!(Testing::from_athena_with_raw(row).is_err());
+    }
+
+    #[test]
+    fn converted_wide_integers() {
+        let parse = |signed: &str, unsigned: &str| {
+            WideTesting::from_athena(HashMap::from([
+                ("signed".to_string(), signed.to_string()),
+                ("unsigned".to_string(), unsigned.to_string()),
+            ]))
+        };
+
+        let res = parse(
+            "-170141183460469231731687303715884105728",
+            "340282366920938463463374607431768211455",
+        )
+        .unwrap();
+        assert_eq!(res.signed, i128::MIN);
+        assert_eq!(res.unsigned, u128::MAX);
+
+        let res = parse("9223372036854775808", "18446744073709551616").unwrap();
+        assert_eq!(res.signed, i64::MAX as i128 + 1);
+        assert_eq!(res.unsigned, u64::MAX as u128 + 1);
+
+        assert_eq!(
+            parse("0", "-1").err().map(|e| format!("{:#}", e)).unwrap(),
+            "WideTesting: Invalid integer within result set. `unsigned` could not parse `-1`: overflow, negative values do not fit the unsigned field type!"
+        );
+    }
+
+    #[test]
+    fn converted_typed_row_to_struct() {
+        let row = vec![("test".to_string(), "bigint".to_string(), "100".to_string())];
+
+        let res = Testing::from_athena_typed(row).unwrap();
+        assert_eq!(res.test, 100);
+    }
+
+    #[test]
+    fn converted_custom_delimiters() {
+        let row = HashMap::from([
+            ("list".to_string(), "[1; 2; 3]".to_string()),
+            ("counts".to_string(), "{a=1, b=2}".to_string()),
+            ("custom".to_string(), "x:1;y:a,b".to_string()),
+        ]);
+
+        let res = DelimiterTesting::from_athena(row).unwrap();
+        assert_eq!(res.list, vec![1, 2, 3]);
+        assert_eq!(
+            res.counts,
+            std::collections::BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+        );
+        assert_eq!(
+            res.custom,
+            HashMap::from([
+                ("x".to_string(), "1".to_string()),
+                ("y".to_string(), "a,b".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn round_trip_to_result_set() {
+        let rows = vec![
+            RoundTripTesting {
+                id: 1,
+                name: Some("first".to_string()),
+                tags: vec!["a".to_string(), "b".to_string()],
+                share: 0.5,
+                active: true,
+                point: (3, 4),
+            },
+            RoundTripTesting {
+                id: 2,
+                name: Some("second".to_string()),
+                tags: vec![],
+                share: 0.25,
+                active: false,
+                point: (-1, 0),
+            },
+        ];
+        let columns = [
+            ("id", "bigint"),
+            ("name", "varchar"),
+            ("tags", "array(varchar)"),
+            ("share", "varchar"),
+            ("active", "integer"),
+            ("point", "varchar"),
+        ];
+
+        let result_set = to_result_set(&rows, &columns);
+        assert_eq!(result_set.rows()[1].data()[3].var_char_value(), Some("25%"));
+
+        let parsed: Vec<RoundTripTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(parsed, rows);
+
+        let unnamed = RoundTripTesting {
+            name: None,
+            ..parsed[0].clone()
+        };
+        let result_set = to_result_set(std::slice::from_ref(&unnamed), &columns);
+        assert_eq!(result_set.rows()[0].data()[1].var_char_value(), None);
+
+        let parsed: Vec<RoundTripTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(parsed, vec![unnamed]);
+    }
+
+    #[test]
+    fn to_athena_renamed_and_optional_fields() {
+        let row = RenameTesting {
+            user_id: 7,
+            note: None,
+        }
+        .to_athena();
+        assert_eq!(
+            row,
+            HashMap::from([("user-id".to_string(), "7".to_string())])
+        );
+
+        let row = RenameTesting {
+            user_id: 7,
+            note: Some("hello".to_string()),
+        }
+        .to_athena();
+        assert_eq!(row["note"], "hello");
+
+        let res = RenameTesting::from_athena(row).unwrap();
+        assert_eq!(res.user_id, 7);
+        assert_eq!(res.note.as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn converted_path_field() {
+        let row = HashMap::from([("key".to_string(), "bucket/logs/2024/01/31.json".to_string())]);
+
+        let res = PathTesting::from_athena(row).unwrap();
+        assert_eq!(
+            res.key,
+            std::path::PathBuf::from("bucket/logs/2024/01/31.json")
+        );
+        assert_eq!(res.to_athena()["key"], "bucket/logs/2024/01/31.json");
+    }
+
+    #[test]
+    fn converted_lenient_field_falls_back() {
+        let row = HashMap::from([
+            ("id".to_string(), "7".to_string()),
+            ("count".to_string(), "-1".to_string()),
+            ("name".to_string(), "test".to_string()),
+        ]);
+
+        let (res, errors) = LenientTesting::from_athena_lenient(row.clone());
+        assert_eq!(res.id, 7);
+        assert_eq!(res.count, 0);
+        assert_eq!(res.name, "test");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "count");
+        assert_eq!(
+            format!("{:#}", errors[0].error),
+            "LenientTesting: Invalid integer within result set. `count` could not parse `-1`: overflow, negative values do not fit the unsigned field type!"
+        );
+
+        let types = HashMap::from([
+            ("id".to_string(), "bigint".to_string()),
+            ("count".to_string(), "integer".to_string()),
+        ]);
+        let (res, errors) = LenientTesting::from_athena_lenient_with_types(&row, &types);
+        assert_eq!((res.id, res.count), (7, 0));
+        assert_eq!(errors[0].field, "count");
+    }
+
+    #[test]
+    fn converted_flatten_delimited_fields() {
+        let row = HashMap::from([
+            ("id".to_string(), "1".to_string()),
+            ("full_name".to_string(), "Smith, John".to_string()),
+        ]);
+
+        let res = SplitTesting::from_athena(row.clone()).unwrap();
+        assert_eq!(res.last, "Smith");
+        assert_eq!(res.first, "John");
+        assert_eq!(SplitTesting::athena_columns(), &["id", "full_name"]);
+        assert_eq!(res.to_athena(), row);
+    }
+
+    #[test]
+    fn error_flatten_delimited_too_few_parts() {
+        let row = HashMap::from([
+            ("id".to_string(), "1".to_string()),
+            ("full_name".to_string(), "Smith".to_string()),
+        ]);
+
+        let res = SplitTesting::from_athena(row);
+        assert_eq!(
+            res.err().map(|e| format!("{:#}", e)).unwrap(),
+            "SplitTesting: Invalid delimited value within result set. `full_name` expected 2 parts but `Smith` has 1!"
+        );
+    }
+
+    #[test]
+    fn build_map_indexed_by_id() {
+        let result_set = result_set(
+            &[("id", "varchar"), ("name", "varchar")],
+            &[&["a", "first"], &["b", "second"], &["a", "third"]],
+        );
+
+        let res = build_map_indexed_by(result_set.clone(), "id", true).unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res["a"]["name"], "third");
+        assert_eq!(res["b"]["name"], "second");
+
+        let res = build_map_indexed_by(result_set, "id", false);
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Duplicate key within result set. `id` has `a` more than once!"
+        );
+    }
+
+    #[test]
+    fn error_decimal_exceeding_scale() {
+        #[derive(from_athena_derive::FromAthena)]
+        struct Price {
+            pub price: f64,
+        }
+
+        let result_set = result_set(&[("price", "decimal(10,2)")], &[&["1.25"], &["1.255"]]);
+        let options = ParserOptions::new().validate_decimals(true);
+
+        let res = parse_result_set_with_options::<Price>(result_set.clone(), &options);
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            "Invalid decimal within result set. `price` could not parse `1.255`: overflow, 3 fractional digits exceed the scale of 2!"
+        );
+
+        let res: Vec<Price> = parse_result_set(result_set).unwrap();
+        assert_eq!(res[1].price, 1.255);
+    }
+
+    #[test]
+    fn converted_locale_floats() {
+        let row = HashMap::from([
+            ("ratio".to_string(), "2,5".to_string()),
+            ("total".to_string(), "1.234.567,5".to_string()),
+        ]);
+
+        let res = LocaleTesting::from_athena(row).unwrap();
+        assert_eq!(res.ratio, 2.5);
+        assert_eq!(res.total, 1234567.5);
+
+        let row = HashMap::from([
+            ("ratio".to_string(), "2,5,1".to_string()),
+            ("total".to_string(), "1".to_string()),
+        ]);
+        assert_eq!(
+            LocaleTesting::from_athena(row)
+                .err()
+                .map(|e| format!("{:#}", e))
+                .unwrap(),
+            "LocaleTesting: Invalid float within result set. `ratio` could not parse `2,5,1`!"
+        );
+    }
+
+    #[test]
+    fn parse_limited_rows() {
+        let result_set = result_set(&[("test", "bigint")], &[&["1"], &["2"], &["x"]]);
+
+        let res: Vec<Testing> = parse_result_set_limited(result_set.clone(), 2).unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[1].test, 2);
+
+        // The unparseable row past the limit is never converted.
+        assert!(parse_result_set::<Testing>(result_set).is_err());
+    }
+
+    #[test]
+    fn parse_rows_with_stats() {
+        let result_set = result_set(&[("test", "bigint")], &[&["1"], &["x"], &["3"]]);
+
+        let (res, stats) = parse_result_set_with_stats::<Testing>(result_set);
+        assert_eq!(res.iter().map(|r| r.test).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(
+            stats,
+            ParseStats {
+                parsed: 2,
+                failed: 1,
+                skipped: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_blank_as_null() {
+        #[derive(from_athena_derive::FromAthena)]
+        struct Blank {
+            pub id: i64,
+            pub count: Option<i64>,
+        }
+
+        let options = ParserOptions::new().blank_as_null(true);
+        let rows = result_set(&[("id", "bigint"), ("count", "bigint")], &[&["1", "  "]]);
+
+        let res: Vec<Blank> = parse_result_set_with_options(rows.clone(), &options).unwrap();
+        assert_eq!(res[0].id, 1);
+        assert_eq!(res[0].count, None);
+        assert!(parse_result_set::<Blank>(rows).is_err());
+
+        let rows = result_set(&[("id", "bigint"), ("count", "bigint")], &[&[" ", "2"]]);
+        let res = parse_result_set_with_options::<Blank>(rows, &options);
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            "Blank value within result set. `id` has no value!"
+        );
+    }
+
+    #[test]
+    fn converted_array_with_null_elements() {
+        let row = HashMap::from([("values".to_string(), "[1, null, 3]".to_string())]);
 
-        let res: Vec<Result<BadTesting, anyhow::Error>> = build_map(result_set)
-            .iter()
-            .map(|x| BadTesting::from_athena(x.clone()))
-            .collect();
+        let res = NullElementTesting::from_athena(row.clone()).unwrap();
+        assert_eq!(res.values, vec![Some(1), None, Some(3)]);
+        assert_eq!(res.to_athena(), row);
 
-        assert!(res[0].is_err());
+        #[derive(from_athena_derive::FromAthena)]
+        struct NotNull {
+            #[allow(dead_code)]
+            pub values: Vec<i64>,
+        }
+        assert_eq!(
+            NotNull::from_athena(row).err().map(|e| format!("{:#}", e)).unwrap(),
+            "NotNull: Invalid array within result set. A `null` element can only be read into an `Option` element!"
+        );
+    }
+
+    #[test]
+    fn converted_raw_identifier_field() {
+        let row = HashMap::from([("type".to_string(), "table".to_string())]);
+
+        let res = RawIdentTesting::from_athena(row.clone()).unwrap();
+        assert_eq!(res.r#type, "table");
+        assert_eq!(RawIdentTesting::athena_columns(), &["type"]);
+        assert_eq!(res.to_athena(), row);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn converted_dates_with_formats() {
+        let result_set = result_set(&[("day", "varchar")], &[&["2024-01-31"], &["02/29/2024"]]);
+
+        let res: Vec<FormatsTesting> = parse_result_set(result_set).unwrap();
+        assert_eq!(
+            res[0].day,
+            chrono::NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+        );
+        assert_eq!(
+            res[1].day,
+            chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+
+        let row = HashMap::from([("day".to_string(), "31.01.2024".to_string())]);
+        assert_eq!(
+            FormatsTesting::from_athena(row).err().map(|e| format!("{:#}", e)).unwrap(),
+            "FormatsTesting: Invalid date within result set. `day` could not parse `31.01.2024` with any of `%Y-%m-%d`, `%m/%d/%Y`!"
+        );
+    }
+
+    #[test]
+    fn converted_multi_byte_char() {
+        let row = HashMap::from([("symbol".to_string(), "€".to_string())]);
+        assert_eq!(CharTesting::from_athena(row).unwrap().symbol, '€');
+
+        let row = HashMap::from([("symbol".to_string(), "ab".to_string())]);
+        assert_eq!(
+            CharTesting::from_athena(row).err().map(|e| format!("{:#}", e)).unwrap(),
+            "CharTesting: Invalid character within result set. `symbol` could not parse `ab`, expected a single character!"
+        );
+
+        let row = HashMap::from([("symbol".to_string(), "".to_string())]);
+        assert!(CharTesting::from_athena(row).is_err());
+    }
+
+    #[test]
+    fn converted_bytes_encoding() {
+        let row = HashMap::from([
+            ("base64".to_string(), "3q2+7w==".to_string()),
+            ("hex".to_string(), "de ad be ef".to_string()),
+            ("utf8".to_string(), "dead".to_string()),
+            ("default".to_string(), "de ad be ef".to_string()),
+        ]);
+
+        let res = BytesEncodingTesting::from_athena(row).unwrap();
+        assert_eq!(res.base64, vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(res.hex, vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(res.utf8, b"dead".to_vec());
+        assert_eq!(res.default, vec![0xde, 0xad, 0xbe, 0xef]);
+
+        // Without `bytes_encoding`, both directions use Athena's hex `varbinary` form.
+        let row = res.to_athena();
+        assert_eq!(row["base64"], "3q2+7w==");
+        assert_eq!(row["hex"], "deadbeef");
+        assert_eq!(row["utf8"], "dead");
+        assert_eq!(row["default"], "deadbeef");
+
+        let parsed = BytesEncodingTesting::from_athena(row).unwrap();
+        assert_eq!(parsed.base64, res.base64);
+        assert_eq!(parsed.hex, res.hex);
+        assert_eq!(parsed.utf8, res.utf8);
+        assert_eq!(parsed.default, res.default);
+
+        // Valid hex, but not a whole number of base64 characters.
+        let row = HashMap::from([
+            ("base64".to_string(), "abcde".to_string()),
+            ("hex".to_string(), "00".to_string()),
+            ("utf8".to_string(), "".to_string()),
+            ("default".to_string(), "".to_string()),
+        ]);
+        assert_eq!(
+            BytesEncodingTesting::from_athena(row).err().map(|e| format!("{:#}", e)).unwrap(),
+            "BytesEncodingTesting: Invalid base64 within result set. `base64` could not be decoded: base64 value has an invalid length"
+        );
+    }
+
+    #[test]
+    fn converted_duration() {
+        use std::time::Duration;
+
+        let row = HashMap::from([
+            ("elapsed".to_string(), "90".to_string()),
+            ("latency".to_string(), "1500".to_string()),
+        ]);
+        let res = DurationTesting::from_athena(row).unwrap();
+        assert_eq!(res.elapsed, Duration::from_secs(90));
+        assert_eq!(res.latency, Duration::from_millis(1500));
+
+        let row = res.to_athena();
+        assert_eq!(row["elapsed"], "90");
+        assert_eq!(row["latency"], "1500");
+
+        let row = HashMap::from([
+            ("elapsed".to_string(), "0.25".to_string()),
+            ("latency".to_string(), "2.5".to_string()),
+        ]);
+        let res = DurationTesting::from_athena(row).unwrap();
+        assert_eq!(res.elapsed, Duration::from_millis(250));
+        assert_eq!(res.latency, Duration::from_micros(2500));
+
+        let row = HashMap::from([
+            ("elapsed".to_string(), "-1".to_string()),
+            ("latency".to_string(), "0".to_string()),
+        ]);
+        assert_eq!(
+            DurationTesting::from_athena(row)
+                .err()
+                .map(|e| format!("{:#}", e))
+                .unwrap(),
+            "DurationTesting: Invalid duration within result set. `elapsed` is negative: `-1`!"
+        );
+    }
+
+    #[test]
+    fn parse_result_set_keyed_by_field() {
+        let rs = result_set(&[("test", "bigint")], &[&["100"], &["200"], &["100"]]);
+        let indexed = parse_result_set_indexed(rs, |t: &Testing| t.test).unwrap();
+        assert_eq!(indexed.len(), 2);
+        assert_eq!(indexed[&200].test, 200);
+
+        let rs = result_set(&[("test", "bigint")], &[&["100"], &["200"]]);
+        let indexed = parse_result_set_indexed_unique(rs, |t: &Testing| t.test).unwrap();
+        assert_eq!(indexed[&100].test, 100);
+
+        let rs = result_set(&[("test", "bigint")], &[&["100"], &["200"], &["100"]]);
+        assert_eq!(
+            parse_result_set_indexed_unique(rs, |t: &Testing| t.test)
+                .err()
+                .unwrap()
+                .to_string(),
+            "Duplicate key within result set. `100` was found more than once!"
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn parse_with_type_overrides() {
+        let result_set = || {
+            result_set(
+                &[("test", "bigint"), ("active", "varchar")],
+                &[&["100", "true"]],
+            )
+        };
+
+        let res: Vec<FlattenJsonTesting> = parse_result_set(result_set()).unwrap();
+        assert_eq!(res[0].rest["active"], serde_json::json!("true"));
+
+        let options = ParserOptions::new().type_overrides(HashMap::from([(
+            "active".to_string(),
+            "boolean".to_string(),
+        )]));
+        let res: Vec<FlattenJsonTesting> =
+            parse_result_set_with_options(result_set(), &options).unwrap();
+        assert_eq!(res[0].rest["active"], serde_json::json!(true));
+    }
+
+    #[test]
+    fn converted_collect_prefix() {
+        let row = HashMap::from([
+            ("id".to_string(), "1".to_string()),
+            ("score_10".to_string(), "10.5".to_string()),
+            ("score_2".to_string(), "2.5".to_string()),
+            ("score_1".to_string(), "1.5".to_string()),
+            ("score_avg".to_string(), "4.8".to_string()),
+        ]);
+        let res = CollectPrefixTesting::from_athena(row).unwrap();
+        assert_eq!(res.scores, vec![1.5, 2.5, 10.5]);
+
+        let row = res.to_athena();
+        assert_eq!(row["score_1"], "1.5");
+        assert_eq!(row["score_3"], "10.5");
+
+        let row = HashMap::from([("id".to_string(), "1".to_string())]);
+        assert!(CollectPrefixTesting::from_athena(row)
+            .unwrap()
+            .scores
+            .is_empty());
+
+        let row = HashMap::from([
+            ("id".to_string(), "1".to_string()),
+            ("score_1".to_string(), "high".to_string()),
+        ]);
+        assert_eq!(
+            CollectPrefixTesting::from_athena(row).err().map(|e| format!("{:#}", e)).unwrap(),
+            "CollectPrefixTesting: Invalid value within result set. `score_1` could not parse `high`: invalid float literal!"
+        );
+    }
+
+    #[test]
+    fn converted_prefix_to_map() {
+        let row = HashMap::from([
+            ("id".to_string(), "1".to_string()),
+            ("metric_2020".to_string(), "1.5".to_string()),
+            ("metric_2021".to_string(), "2.5".to_string()),
+            ("other".to_string(), "3.5".to_string()),
+        ]);
+        let res = PrefixMapTesting::from_athena(row).unwrap();
+        assert_eq!(
+            res.metrics,
+            HashMap::from([("2020".to_string(), 1.5), ("2021".to_string(), 2.5)])
+        );
+
+        let row = res.to_athena();
+        assert_eq!(row["metric_2020"], "1.5");
+        assert_eq!(row["metric_2021"], "2.5");
+
+        let row = HashMap::from([
+            ("id".to_string(), "1".to_string()),
+            ("metric_2020".to_string(), "high".to_string()),
+        ]);
+        assert_eq!(
+            PrefixMapTesting::from_athena(row).err().map(|e| format!("{:#}", e)).unwrap(),
+            "PrefixMapTesting: Invalid value within result set. `metric_2020` could not parse `high`: invalid float literal!"
+        );
+    }
+
+    #[test]
+    fn parse_result_set_skipping_repeated_headers() {
+        let rows = || {
+            result_set(
+                &[("test", "bigint")],
+                &[&["test"], &["100"], &["test"], &["200"]],
+            )
+        };
+
+        assert!(parse_result_set::<Testing>(rows()).is_err());
+
+        let res = parse_result_set_dedup_header::<Testing>(rows()).unwrap();
+        assert_eq!(
+            res.iter().map(|t| t.test).collect::<Vec<_>>(),
+            vec![100, 200]
+        );
+    }
+
+    #[test]
+    fn converted_string_as_is() {
+        let value = " [a, b] {k=v} \"quoted\" 1,5% null\t€ ";
+        let row = HashMap::from([
+            ("id".to_string(), "100".to_string()),
+            ("name".to_string(), value.to_string()),
+        ]);
+
+        let res = SubsetTesting::from_athena(row).unwrap();
+        assert_eq!(res.name, value);
+    }
+
+    #[test]
+    fn converted_serde_rename() {
+        let row = HashMap::from([
+            ("userId".to_string(), "7".to_string()),
+            ("display-name".to_string(), "test".to_string()),
+            ("email_address".to_string(), "test@example.com".to_string()),
+            ("created".to_string(), "1700000000".to_string()),
+        ]);
+
+        let res = SerdeRenameTesting::from_athena(row.clone()).unwrap();
+        assert_eq!(res.user_id, 7);
+        assert_eq!(res.name, "test");
+        assert_eq!(res.email, "test@example.com");
+        assert_eq!(res.created_at, 1_700_000_000);
+        assert_eq!(res.to_athena(), row);
+    }
+
+    #[test]
+    fn converted_enum_list() {
+        let row = HashMap::from([("permissions".to_string(), "READ,WRITE".to_string())]);
+        let res = PermissionTesting::from_athena(row).unwrap();
+        assert_eq!(res.permissions, vec![Permission::Read, Permission::Write]);
+        assert_eq!(res.to_athena()["permissions"], "[READ, WRITE]");
+
+        let row = HashMap::from([("permissions".to_string(), "READ,DELETE".to_string())]);
+        assert_eq!(
+            PermissionTesting::from_athena(row).err().map(|e| format!("{:#}", e)).unwrap(),
+            "PermissionTesting: Invalid Permission within result set. `DELETE` is not one of `READ`, `WRITE` or `EXEC`!"
+        );
+    }
+
+    #[test]
+    fn converted_raw_identifier_variant() {
+        #[derive(
+            Debug, PartialEq, from_athena_derive::FromAthena, from_athena_derive::ToAthena,
+        )]
+        #[allow(non_camel_case_types)]
+        enum Keyword {
+            r#type,
+            r#match,
+        }
+
+        assert_eq!("type".parse::<Keyword>().unwrap(), Keyword::r#type);
+        assert!("r#type".parse::<Keyword>().is_err());
+        assert_eq!(Keyword::r#match.to_string(), "match");
+    }
+
+    #[test]
+    fn parse_with_sanitized_values() {
+        let rows = || result_set(&[("test", "bigint")], &[&["\u{feff}100"], &["2\u{0}00"]]);
+
+        assert!(parse_result_set::<Testing>(rows()).is_err());
+
+        let options = ParserOptions::new().sanitize(true);
+        let res: Vec<Testing> = parse_result_set_with_options(rows(), &options).unwrap();
+        assert_eq!(
+            res.iter().map(|t| t.test).collect::<Vec<_>>(),
+            vec![100, 200]
+        );
+
+        let mut value = "a\tb\u{7}".to_string();
+        options::sanitize(&mut value);
+        assert_eq!(value, "a\tb");
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn converted_big_decimal() {
+        let value = "98765432109876543210.123456789012345678";
+        let row = HashMap::from([("amount".to_string(), value.to_string())]);
+
+        let res = BigDecimalTesting::from_athena(row.clone()).unwrap();
+        assert_eq!(res.amount.to_string(), value);
+        assert_eq!(res.to_athena(), row);
+    }
+
+    #[test]
+    fn converted_within_range() {
+        let row = |age: &str, score: &str| {
+            HashMap::from([
+                ("age".to_string(), age.to_string()),
+                ("score".to_string(), score.to_string()),
+            ])
+        };
+
+        let res = RangeTesting::from_athena(row("150", "-1.5")).unwrap();
+        assert_eq!((res.age, res.score), (150, Some(-1.5)));
+        let res = RangeTesting::from_athena(HashMap::from([("age".to_string(), "0".to_string())]));
+        assert_eq!(res.unwrap().score, None);
+
+        assert_eq!(
+            RangeTesting::from_athena(row("-1", "0")).err().map(|e| format!("{:#}", e)).unwrap(),
+            "RangeTesting: Out of range value within result set. `age` is `-1`, below the minimum of `0`!"
+        );
+        assert_eq!(
+            RangeTesting::from_athena(row("151", "0")).err().map(|e| format!("{:#}", e)).unwrap(),
+            "RangeTesting: Out of range value within result set. `age` is `151`, above the maximum of `150`!"
+        );
+        assert_eq!(
+            RangeTesting::from_athena(row("20", "-2")).err().map(|e| format!("{:#}", e)).unwrap(),
+            "RangeTesting: Out of range value within result set. `score` is `-2`, below the minimum of `-1.5`!"
+        );
+    }
+
+    #[test]
+    fn dispatch_erased_parsers_by_key() {
+        let registry: HashMap<&str, ErasedParser> = HashMap::from([
+            ("testing", erased_parser::<Testing>()),
+            ("subset", erased_parser::<SubsetTesting>()),
+        ]);
+
+        let row = HashMap::from([
+            ("test".to_string(), "100".to_string()),
+            ("id".to_string(), "7".to_string()),
+            ("name".to_string(), "first".to_string()),
+        ]);
+        let parsed = registry["testing"](row.clone()).unwrap();
+        assert_eq!(parsed.downcast::<Testing>().unwrap().test, 100);
+
+        let parsed = registry["subset"](row).unwrap();
+        assert!(parsed.downcast_ref::<Testing>().is_none());
+        assert_eq!(parsed.downcast::<SubsetTesting>().unwrap().name, "first");
+
+        let row = HashMap::from([("test".to_string(), "x".to_string())]);
+        assert!(registry["testing"](row).is_err());
+    }
+
+    #[test]
+    fn parse_from_row_sources() {
+        let row = HashMap::from([
+            ("test".to_string(), "100".to_string()),
+            ("other".to_string(), "value".to_string()),
+        ]);
+        let res = FlattenTesting::from_athena_source(&row, &HashMap::new()).unwrap();
+        assert_eq!((res.test, res.rest["other"].as_str()), (100, "value"));
+
+        let row: std::collections::BTreeMap<_, _> = row.into_iter().collect();
+        let res = FlattenTesting::from_athena_source(&row, &HashMap::new()).unwrap();
+        assert_eq!((res.test, res.rest["other"].as_str()), (100, "value"));
+
+        let row = std::collections::BTreeMap::from([("other".to_string(), "1".to_string())]);
+        assert_eq!(
+            Testing::from_athena_source(&row, &HashMap::new())
+                .err()
+                .map(|e| format!("{:#}", e))
+                .unwrap(),
+            "Testing: Missing field within result set. `test` was not found!"
+        );
+    }
+
+    #[cfg(feature = "ordered")]
+    #[test]
+    fn parse_from_ordered_map() {
+        let rows = build_ordered_map(result_set(
+            &[("test", "bigint"), ("b", "varchar"), ("a", "varchar")],
+            &[&["100", "first", "second"]],
+        ));
+        assert_eq!(rows[0].keys().collect::<Vec<_>>(), vec!["test", "b", "a"]);
+
+        let res = FlattenTesting::from_athena_source(&rows[0], &HashMap::new()).unwrap();
+        assert_eq!(res.test, 100);
+        assert_eq!(res.rest["a"], "second");
+
+        let row = IndexMap::from([("test".to_string(), "x".to_string())]);
+        assert_eq!(
+            Testing::from_athena_source(&row, &HashMap::new())
+                .err()
+                .map(|e| format!("{:#}", e))
+                .unwrap(),
+            "Testing: Invalid integer within result set. `test` could not parse `x`: invalid digit!"
+        );
+    }
+
+    #[cfg(feature = "ordered")]
+    #[test]
+    fn build_ordered_map_leaves_out_unknown_columns() {
+        let result_set = crate::test_util::result_set_with_nulls(
+            &[
+                ("test", "bigint"),
+                ("nothing", "unknown"),
+                ("name", "varchar"),
+            ],
+            &[&[Some("100"), None, None]],
+        );
+
+        let rows = build_ordered_map(result_set.clone());
+        assert_eq!(rows[0].keys().collect::<Vec<_>>(), vec!["test"]);
+        assert_eq!(
+            rows[0].clone().into_iter().collect::<HashMap<_, _>>(),
+            build_map(result_set)[0]
+        );
+    }
+
+    #[test]
+    fn converted_signed_numbers() {
+        let row = |count: &str, score: &str, normalized: &str| {
+            HashMap::from([
+                ("count".to_string(), count.to_string()),
+                ("score".to_string(), score.to_string()),
+                ("normalized".to_string(), normalized.to_string()),
+            ])
+        };
+
+        let res = SignTesting::from_athena(row("+5", "-0.0", "-0.0")).unwrap();
+        assert_eq!(res.count, 5);
+        assert_eq!(res.score, 0.0);
+        assert!(res.score.is_sign_negative());
+        assert_eq!(res.normalized, Some(0.0));
+        assert!(res.normalized.unwrap().is_sign_positive());
+
+        let res = SignTesting::from_athena(row("-0", "+1.5", "-2.5")).unwrap();
+        assert_eq!((res.count, res.score, res.normalized), (0, 1.5, Some(-2.5)));
+    }
+
+    #[test]
+    fn converted_flattened_structs_sharing_a_column() {
+        let row = HashMap::from([
+            ("id".to_string(), "7".to_string()),
+            ("name".to_string(), "first".to_string()),
+            ("score".to_string(), "1.5".to_string()),
+            ("extra".to_string(), "value".to_string()),
+        ]);
+        let mut res = ComposedTesting::from_athena(row.clone()).unwrap();
+        assert_eq!((res.name.id, res.name.name.as_str()), (7, "first"));
+        assert_eq!((res.score.id, res.score.score), (7, 1.5));
+        assert_eq!(
+            res.rest,
+            HashMap::from([("extra".to_string(), "value".to_string())])
+        );
+        assert_eq!(res.to_athena(), row);
+
+        assert_eq!(
+            overlapping_columns(&[NamePart::athena_columns(), ScorePart::athena_columns()]),
+            vec!["id"]
+        );
+
+        // The flattened field declared last decides a shared column.
+        res.score.id = 8;
+        assert_eq!(res.to_athena()["id"], "8");
+
+        let row = HashMap::from([("id".to_string(), "7".to_string())]);
         assert_eq!(
-            res[0].as_ref().err().unwrap().to_string(),
-            "Missing field within result set. `no_exist` was not found!".to_string()
+            ComposedTesting::from_athena(row)
+                .err()
+                .map(|e| format!("{:#}", e))
+                .unwrap(),
+            "ComposedTesting: NamePart: Missing field within result set. `name` was not found!"
         );
     }
 }
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+/// Longest value, in characters, kept when a row is summarised in an error.
+const MAX_SUMMARY_VALUE_LEN: usize = 64;
+
+/// Options controlling how rows are parsed by [`parse_result_set_with_options`](crate::parse_result_set_with_options)
+/// and mapped by [`build_map_with_options`](crate::build_map_with_options).
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::ParserOptions;
+///
+/// let options = ParserOptions::new().include_row_in_error(true);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParserOptions {
+    pub(crate) include_row_in_error: bool,
+    pub(crate) unquote_column_names: bool,
+    pub(crate) key_by_label: bool,
+    pub(crate) validate_decimals: bool,
+    pub(crate) blank_as_null: bool,
+    pub(crate) type_overrides: HashMap<String, String>,
+    pub(crate) skip_header: bool,
+    pub(crate) skip_repeated_headers: bool,
+    pub(crate) sanitize: bool,
+}
+
+impl ParserOptions {
+    /// Creates the default set of options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attaches a `{column: value}` summary of the offending row to row errors.
+    ///
+    /// Values longer than 64 characters are truncated. This is off by default as
+    /// rows may contain personal or otherwise sensitive data.
+    pub fn include_row_in_error(mut self, include: bool) -> Self {
+        self.include_row_in_error = include;
+        self
+    }
+
+    /// Strips surrounding double quotes from column names, so `"my col"` is read as `my col`.
+    ///
+    /// Some catalogs return quoted column names, which would otherwise not match the
+    /// field names of a struct. This is off by default.
+    pub fn unquote_column_names(mut self, unquote: bool) -> Self {
+        self.unquote_column_names = unquote;
+        self
+    }
+
+    /// Keys each row by the column label rather than its name.
+    ///
+    /// The label holds the alias given with `AS`, which can differ from the name, so a
+    /// struct field can match the alias instead. Columns without a label keep their name.
+    /// This is off by default.
+    pub fn key_by_label(mut self, key_by_label: bool) -> Self {
+        self.key_by_label = key_by_label;
+        self
+    }
+
+    /// Rejects values of `decimal(p,s)` columns with more than `s` fractional digits, or
+    /// more than `p - s` integer digits, before the row is converted.
+    ///
+    /// The precision and scale are read from the column type in the metadata. This is off
+    /// by default.
+    pub fn validate_decimals(mut self, validate: bool) -> Self {
+        self.validate_decimals = validate;
+        self
+    }
+
+    /// Treats values made only of whitespace, such as `"   "`, as missing.
+    ///
+    /// An `Option<T>` field reading a blank value is `None`. Any other field reading one
+    /// fails with a blank value error rather than a parse error. This is off by default.
+    pub fn blank_as_null(mut self, blank_as_null: bool) -> Self {
+        self.blank_as_null = blank_as_null;
+        self
+    }
+
+    /// Drops the first row when its values all equal their column names.
+    ///
+    /// Athena returns such a header row ahead of the data on the first page of a query's
+    /// results. This is off by default, as a data row could legitimately hold its column
+    /// names, and the paging functions of the `client` feature already drop it.
+    pub fn skip_header(mut self, skip: bool) -> Self {
+        self.skip_header = skip;
+        self
+    }
+
+    /// Drops every row whose values all equal their column names, not only the first one.
+    ///
+    /// Header-like rows can appear in the middle of the results of `UNION` queries or
+    /// `SHOW` statements. This is off by default, as a data row could legitimately hold
+    /// its column names.
+    pub fn skip_repeated_headers(mut self, skip: bool) -> Self {
+        self.skip_repeated_headers = skip;
+        self
+    }
+
+    /// Strips a leading UTF-8 byte order mark and any control characters other than
+    /// whitespace, such as `\u{0}`, from every value before it is parsed.
+    ///
+    /// Tabs and line breaks are kept, as text columns may legitimately hold them. This is
+    /// off by default.
+    pub fn sanitize(mut self, sanitize: bool) -> Self {
+        self.sanitize = sanitize;
+        self
+    }
+
+    /// Replaces the type the metadata reports for the given columns, keyed by column name.
+    ///
+    /// The overriding SQL type, such as `boolean`, is used wherever the column type
+    /// matters, such as the values given to a `serde_json::Map` flatten field or decimal
+    /// validation. This helps when a catalog reports a generic type such as `varchar` for
+    /// a column that holds something more specific. No columns are overridden by default.
+    pub fn type_overrides(mut self, type_overrides: HashMap<String, String>) -> Self {
+        self.type_overrides = type_overrides;
+        self
+    }
+}
+
+/// Strips one pair of surrounding double quotes from a column name, if present.
+pub(crate) fn unquote(name: &str) -> String {
+    name.strip_prefix('"')
+        .and_then(|name| name.strip_suffix('"'))
+        .unwrap_or(name)
+        .to_string()
+}
+
+/// Strips a leading byte order mark and non-whitespace control characters from a value,
+/// for [`ParserOptions::sanitize`].
+pub(crate) fn sanitize(value: &mut String) {
+    let stripped = value.strip_prefix('\u{feff}').unwrap_or(value);
+    if stripped.len() == value.len() && !value.chars().any(is_stray_control) {
+        return;
+    }
+
+    *value = stripped.chars().filter(|c| !is_stray_control(*c)).collect();
+}
+
+fn is_stray_control(c: char) -> bool {
+    c.is_control() && !c.is_whitespace()
+}
+
+/// Renders a row as `{column: value, ...}` ordered by column name, truncating long values.
+pub(crate) fn summarize_row(row: &HashMap<String, String>) -> String {
+    let mut columns: Vec<(&String, &String)> = row.iter().collect();
+    columns.sort();
+
+    let entries: Vec<String> = columns
+        .iter()
+        .map(|(col, val)| {
+            if val.chars().count() > MAX_SUMMARY_VALUE_LEN {
+                let truncated: String = val.chars().take(MAX_SUMMARY_VALUE_LEN).collect();
+                format!("{}: {}...", col, truncated)
+            } else {
+                format!("{}: {}", col, val)
+            }
+        })
+        .collect();
+
+    format!("{{{}}}", entries.join(", "))
+}
@@ -0,0 +1,225 @@
+use crate::field::{missing_field, parse_int};
+use crate::HashMap;
+use std::str::FromStr;
+
+/// Typed getters for reading a row by column name without deriving [`FromAthena`](crate::FromAthena).
+///
+/// Each getter parses the value on demand and names the column in its error, using the
+/// same messages as the code generated by the derive.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::{AthenaRowExt, HashMap};
+///
+/// let row = HashMap::from([
+///     ("id".to_string(), "7".to_string()),
+///     ("active".to_string(), "true".to_string()),
+/// ]);
+///
+/// assert_eq!(row.get_i64("id").unwrap(), 7);
+/// assert!(row.get_bool("active").unwrap());
+/// assert!(row.get_string("missing").is_err());
+/// ```
+pub trait AthenaRowExt {
+    /// Returns the raw value of `column`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming `column` if the row has no value for it.
+    fn get_str(&self, column: &str) -> anyhow::Result<&str>;
+
+    /// Returns the value of `column` parsed with `str::parse`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming `column` if the row has no value for it or the value does
+    /// not parse as `T`.
+    fn get_parsed<T>(&self, column: &str) -> anyhow::Result<T>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        let value = self.get_str(column)?;
+        value.parse::<T>().map_err(|e| {
+            anyhow::Error::msg(format!(
+                "Invalid value within result set. `{}` could not parse `{}`: {}!",
+                column, value, e
+            ))
+        })
+    }
+
+    /// Returns the value of `column` as a `String`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming `column` if the row has no value for it.
+    fn get_string(&self, column: &str) -> anyhow::Result<String> {
+        self.get_str(column).map(str::to_string)
+    }
+
+    /// Returns the value of `column` as an `i32`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming `column` if the row has no value for it or the value is not
+    /// an `i32`.
+    fn get_i32(&self, column: &str) -> anyhow::Result<i32> {
+        parse_int(column, self.get_str(column)?)
+    }
+
+    /// Returns the value of `column` as an `i64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming `column` if the row has no value for it or the value is not
+    /// an `i64`.
+    fn get_i64(&self, column: &str) -> anyhow::Result<i64> {
+        parse_int(column, self.get_str(column)?)
+    }
+
+    /// Returns the value of `column` as an `f64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming `column` if the row has no value for it or the value is not
+    /// a number.
+    fn get_f64(&self, column: &str) -> anyhow::Result<f64> {
+        self.get_parsed(column)
+    }
+
+    /// Returns the value of `column` as a `bool`, which Athena renders as `true` or `false`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming `column` if the row has no value for it or the value is not
+    /// a boolean.
+    fn get_bool(&self, column: &str) -> anyhow::Result<bool> {
+        self.get_parsed(column)
+    }
+}
+
+impl AthenaRowExt for HashMap<String, String> {
+    fn get_str(&self, column: &str) -> anyhow::Result<&str> {
+        self.get(column)
+            .map(String::as_str)
+            .ok_or_else(|| missing_field(column))
+    }
+}
+
+/// A row that [`FromAthena::from_athena_source`](crate::FromAthena::from_athena_source) can
+/// read columns from, so the code generated by the derive does not depend on the map type.
+///
+/// This is implemented for `HashMap<String, String>`, `BTreeMap<String, String>`, the
+/// borrowed rows built by [`build_map_borrowed`](crate::build_map_borrowed) and, with the
+/// `ordered` feature, `IndexMap<String, String>` such as the rows built by
+/// [`build_ordered_map`](crate::build_ordered_map). It is sealed, so it cannot be
+/// implemented outside this crate.
+pub trait RowSource: sealed::Sealed {
+    /// Returns the value of `column`, if the row has one.
+    fn get_value(&self, column: &str) -> Option<&str>;
+
+    /// Iterates over the names of the columns the row has a value for.
+    fn keys(&self) -> impl Iterator<Item = &str>;
+}
+
+mod sealed {
+    /// Keeps [`RowSource`](super::RowSource) from being implemented outside this crate.
+    pub trait Sealed {}
+}
+
+/// Iterates over the `(column, value)` pairs of a row, for the helpers of `flatten` and
+/// `collect_prefix` fields.
+pub(crate) fn entries<R: RowSource + ?Sized>(row: &R) -> impl Iterator<Item = (&str, &str)> {
+    row.keys()
+        .filter_map(|column| Some((column, row.get_value(column)?)))
+}
+
+macro_rules! impl_row_source {
+    ($($map:ty),*) => {$(
+        impl sealed::Sealed for $map {}
+
+        impl RowSource for $map {
+            fn get_value(&self, column: &str) -> Option<&str> {
+                self.get(column).map(String::as_str)
+            }
+
+            fn keys(&self) -> impl Iterator<Item = &str> {
+                <$map>::keys(self).map(String::as_str)
+            }
+        }
+    )*};
+}
+
+impl_row_source!(HashMap<String, String>, std::collections::BTreeMap<String, String>);
+
+impl sealed::Sealed for HashMap<&str, &str> {}
+
+impl RowSource for HashMap<&str, &str> {
+    fn get_value(&self, column: &str) -> Option<&str> {
+        self.get(column).copied()
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &str> {
+        HashMap::keys(self).copied()
+    }
+}
+#[cfg(feature = "ordered")]
+impl_row_source!(indexmap::IndexMap<String, String>);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn row() -> HashMap<String, String> {
+        HashMap::from([
+            ("id".to_string(), "100".to_string()),
+            ("active".to_string(), "true".to_string()),
+            ("name".to_string(), "test".to_string()),
+        ])
+    }
+
+    #[test]
+    fn get_i64_from_row() {
+        assert_eq!(row().get_i64("id").unwrap(), 100);
+        assert_eq!(
+            row().get_i64("name").unwrap_err().to_string(),
+            "Invalid integer within result set. `name` could not parse `test`: invalid digit!"
+        );
+    }
+
+    #[test]
+    fn get_bool_from_row() {
+        assert!(row().get_bool("active").unwrap());
+        assert_eq!(
+            row().get_bool("id").unwrap_err().to_string(),
+            "Invalid value within result set. `id` could not parse `100`: provided string was not `true` or `false`!"
+        );
+    }
+
+    #[test]
+    fn row_source_of_map_types() {
+        let hash_map = row();
+        let btree_map: std::collections::BTreeMap<String, String> = row().into_iter().collect();
+
+        assert_eq!(hash_map.get_value("id"), Some("100"));
+        assert_eq!(btree_map.get_value("id"), Some("100"));
+        assert_eq!(btree_map.get_value("missing"), None);
+        assert_eq!(
+            RowSource::keys(&btree_map).collect::<Vec<_>>(),
+            vec!["active", "id", "name"]
+        );
+
+        let mut keys: Vec<_> = RowSource::keys(&hash_map).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["active", "id", "name"]);
+    }
+
+    #[test]
+    fn error_get_missing_column() {
+        assert_eq!(
+            row().get_string("missing").unwrap_err().to_string(),
+            "Missing field within result set. `missing` was not found!"
+        );
+    }
+}
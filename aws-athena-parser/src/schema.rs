@@ -0,0 +1,430 @@
+use crate::FromAthena;
+use aws_sdk_athena::types::{ColumnInfo, ResultSet};
+use std::fmt;
+
+/// The differences between the columns of two result sets.
+///
+/// Column names are listed in the order they appear in the result set they were found in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    /// Columns present in the second result set but not the first.
+    pub added: Vec<String>,
+    /// Columns present in the first result set but not the second.
+    pub removed: Vec<String>,
+    /// Columns present in both result sets with a different type.
+    pub type_changed: Vec<String>,
+}
+
+impl SchemaDiff {
+    /// Returns `true` when both result sets have the same columns and types.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.type_changed.is_empty()
+    }
+}
+
+/// Compares the column metadata of two result sets.
+///
+/// Columns are matched by name, so a change in column order alone is not reported.
+/// A result set without metadata is treated as having no columns.
+///
+/// # Arguments
+///
+/// * `a` - The earlier ResultSet.
+/// * `b` - The later ResultSet.
+///
+/// # Returns
+///
+/// A [`SchemaDiff`] describing the columns added, removed and retyped from `a` to `b`.
+pub fn diff_schema(a: &ResultSet, b: &ResultSet) -> SchemaDiff {
+    let before = columns(a);
+    let after = columns(b);
+
+    let find = |columns: &[ColumnInfo], name: &str| {
+        columns
+            .iter()
+            .find(|c| c.name() == name)
+            .map(|c| c.r#type().to_string())
+    };
+
+    let mut diff = SchemaDiff::default();
+
+    for column in after {
+        if find(before, column.name()).is_none() {
+            diff.added.push(column.name().to_string());
+        }
+    }
+
+    for column in before {
+        match find(after, column.name()) {
+            None => diff.removed.push(column.name().to_string()),
+            Some(ty) if ty != column.r#type() => diff.type_changed.push(column.name().to_string()),
+            Some(_) => {}
+        }
+    }
+
+    diff
+}
+
+/// The metadata of a result set column, see [`column_meta`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMeta {
+    /// The name of the column.
+    pub name: String,
+    /// The label of the column, such as the alias given with `AS`. `None` when the catalog
+    /// did not report one.
+    pub label: Option<String>,
+    /// The Athena type of the column, such as `bigint` or `decimal(10,2)`.
+    pub athena_type: String,
+}
+
+impl ColumnMeta {
+    /// Returns the label of the column, or its name when it has no label.
+    pub fn label_or_name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Lists the columns of a result set in order, with both their name and label.
+///
+/// An empty label is treated as missing. A result set without metadata has no columns.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::column_meta;
+/// use aws_sdk_athena::types::{ColumnInfo, ResultSet, ResultSetMetadata};
+///
+/// let column = ColumnInfo::builder()
+///     .name("_col0")
+///     .label("total")
+///     .r#type("bigint")
+///     .build()
+///     .unwrap();
+/// let result_set = ResultSet::builder()
+///     .result_set_metadata(ResultSetMetadata::builder().column_info(column).build())
+///     .build();
+///
+/// let columns = column_meta(&result_set);
+/// assert_eq!(columns[0].name, "_col0");
+/// assert_eq!(columns[0].label_or_name(), "total");
+/// ```
+pub fn column_meta(result_set: &ResultSet) -> Vec<ColumnMeta> {
+    columns(result_set)
+        .iter()
+        .map(|c| ColumnMeta {
+            name: c.name().to_string(),
+            label: c
+                .label()
+                .filter(|label| !label.is_empty())
+                .map(str::to_string),
+            athena_type: c.r#type().to_string(),
+        })
+        .collect()
+}
+
+/// Returns the names of the columns of a result set in order, without building any rows.
+///
+/// A result set without metadata has no columns, so this returns an empty `Vec`.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_athena::types::ResultSet;
+/// use aws_athena_parser::column_names;
+///
+/// assert!(column_names(&ResultSet::builder().build()).is_empty());
+/// ```
+pub fn column_names(result_set: &ResultSet) -> Vec<String> {
+    columns(result_set)
+        .iter()
+        .map(|c| c.name().to_string())
+        .collect()
+}
+
+/// Returns the columns named in more than one of the given lists, in the order they are
+/// first named.
+///
+/// Passing the [`FromAthena::athena_columns`] of the structs flattened into another with
+/// `#[athena(flatten)]` lists the columns they share. Each of them reads a shared column,
+/// while the flattened field declared last decides its value when the struct is written
+/// back with `ToAthena`, so a test can assert this is empty to keep them apart.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::overlapping_columns;
+///
+/// let shared = overlapping_columns(&[&["id", "name"], &["id", "score"], &["score"]]);
+/// assert_eq!(shared, vec!["id", "score"]);
+/// ```
+pub fn overlapping_columns(column_sets: &[&[&'static str]]) -> Vec<&'static str> {
+    let mut seen = Vec::new();
+    let mut overlapping = Vec::new();
+
+    for columns in column_sets {
+        for (index, column) in columns.iter().enumerate() {
+            // A column listed twice by the same struct is not shared.
+            if columns[..index].contains(column) {
+                continue;
+            }
+            if seen.contains(column) {
+                overlapping.push(*column);
+            } else {
+                seen.push(*column);
+            }
+        }
+    }
+
+    seen.retain(|column| overlapping.contains(column));
+    seen
+}
+
+/// What a struct field requires of the column it reads, see
+/// [`FromAthena::athena_column_specs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSpec {
+    /// The column the field reads.
+    pub name: &'static str,
+    /// The last segment of the field type, such as `i64`, when its value is parsed from the
+    /// column as is. `None` when an attribute decides how the value is parsed.
+    pub rust_type: Option<&'static str>,
+    /// Whether the column must be present, which is the case unless the field is an `Option`.
+    pub required: bool,
+}
+
+/// The ways a result set fails to satisfy a struct, returned by [`columns_match_struct`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Required columns the result set does not have.
+    pub missing: Vec<String>,
+    /// Columns whose Athena type cannot be parsed into the field type, as
+    /// `(column, Athena type, field type)`.
+    pub type_conflicts: Vec<(String, String, String)>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Result set does not match the struct.")?;
+        if !self.missing.is_empty() {
+            write!(f, " Missing columns: `{}`.", self.missing.join("`, `"))?;
+        }
+        for (column, athena_type, rust_type) in &self.type_conflicts {
+            write!(
+                f,
+                " `{}` is `{}`, which cannot be parsed into `{}`.",
+                column, athena_type, rust_type
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// Checks once that a result set has the columns `T` reads, with types its fields can
+/// be parsed from.
+///
+/// Required columns must be present, and not of the `unknown` type which only ever holds
+/// NULL. A field read with `#[athena(position = N)]` only requires the result set to have
+/// a column at that index. Integer fields require an integer column, floating point fields
+/// a numeric column and `bool` fields a `boolean` column. Other fields, and fields whose
+/// parsing is decided by an attribute, accept any column type.
+///
+/// # Arguments
+///
+/// * `result_set` - The ResultSet whose column metadata is checked. Its rows are not read.
+///
+/// # Errors
+///
+/// Returns a [`Mismatch`] listing every missing column and type conflict.
+pub fn columns_match_struct<T: FromAthena>(result_set: &ResultSet) -> Result<(), Mismatch> {
+    let columns = columns(result_set);
+    let mut mismatch = Mismatch::default();
+
+    for spec in T::athena_column_specs() {
+        if let Some((index, _)) = T::athena_positions()
+            .iter()
+            .find(|(_, field)| *field == spec.name)
+        {
+            if spec.required && *index >= columns.len() {
+                mismatch.missing.push(spec.name.to_string());
+            }
+            continue;
+        }
+
+        let column = columns
+            .iter()
+            .find(|c| c.name() == spec.name && !base_type(c.r#type()).eq("unknown"));
+
+        match (column, spec.rust_type) {
+            (None, _) if spec.required => mismatch.missing.push(spec.name.to_string()),
+            (Some(column), Some(rust_type)) if !compatible(column.r#type(), rust_type) => {
+                mismatch.type_conflicts.push((
+                    spec.name.to_string(),
+                    column.r#type().to_string(),
+                    rust_type.to_string(),
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    if mismatch.missing.is_empty() && mismatch.type_conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatch)
+    }
+}
+
+/// Returns the lowercase Athena type without its parameters, so `decimal(10,2)` is `decimal`
+/// and `varchar(20)` is `varchar`.
+pub(crate) fn base_type(athena_type: &str) -> String {
+    athena_type
+        .split('(')
+        .next()
+        .unwrap_or(athena_type)
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Returns whether a column of `athena_type` can be parsed into a field of `rust_type`.
+fn compatible(athena_type: &str, rust_type: &str) -> bool {
+    const INTEGERS: &[&str] = &["tinyint", "smallint", "integer", "int", "bigint"];
+    const FLOATS: &[&str] = &["real", "float", "double", "decimal"];
+
+    let athena_type = base_type(athena_type);
+    let athena_type = athena_type.as_str();
+
+    match rust_type {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => INTEGERS.contains(&athena_type),
+        "f32" | "f64" | "BigDecimal" => {
+            INTEGERS.contains(&athena_type) || FLOATS.contains(&athena_type)
+        }
+        "bool" => athena_type == "boolean",
+        _ => true,
+    }
+}
+
+fn columns(result_set: &ResultSet) -> &[ColumnInfo] {
+    result_set
+        .result_set_metadata()
+        .map(|meta| meta.column_info())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::result_set;
+    use crate::{anyhow, HashMap};
+
+    #[test]
+    fn diff_added_and_retyped_columns() {
+        let a = result_set(&[("id", "integer"), ("name", "varchar")], &[]);
+        let b = result_set(
+            &[("id", "bigint"), ("name", "varchar"), ("email", "varchar")],
+            &[],
+        );
+
+        let diff = diff_schema(&a, &b);
+        assert_eq!(
+            diff,
+            SchemaDiff {
+                added: vec!["email".to_string()],
+                removed: vec![],
+                type_changed: vec!["id".to_string()],
+            }
+        );
+        assert!(!diff.is_empty());
+        assert!(diff_schema(&a, &a).is_empty());
+    }
+
+    #[allow(dead_code)]
+    #[derive(crate::FromAthena)]
+    struct Testing {
+        pub id: i64,
+        pub name: String,
+        pub note: Option<String>,
+    }
+
+    #[test]
+    fn column_names_in_order() {
+        let result_set = result_set(&[("id", "bigint"), ("name", "varchar")], &[&["1", "a"]]);
+        assert_eq!(column_names(&result_set), vec!["id", "name"]);
+        assert!(column_names(&ResultSet::builder().build()).is_empty());
+    }
+
+    #[test]
+    fn column_meta_with_labels() {
+        let result_set = ResultSet::builder()
+            .result_set_metadata(
+                aws_sdk_athena::types::ResultSetMetadata::builder()
+                    .column_info(
+                        ColumnInfo::builder()
+                            .name("_col0")
+                            .label("total")
+                            .r#type("bigint")
+                            .build()
+                            .unwrap(),
+                    )
+                    .column_info(
+                        ColumnInfo::builder()
+                            .name("id")
+                            .label("")
+                            .r#type("integer")
+                            .build()
+                            .unwrap(),
+                    )
+                    .build(),
+            )
+            .build();
+
+        let columns = column_meta(&result_set);
+        assert_eq!(columns[0].label.as_deref(), Some("total"));
+        assert_eq!(columns[0].label_or_name(), "total");
+        assert_eq!(columns[1].label, None);
+        assert_eq!(columns[1].label_or_name(), "id");
+    }
+
+    #[test]
+    fn columns_match_struct_reports_mismatches() {
+        let matching = result_set(&[("id", "bigint"), ("name", "varchar")], &[]);
+        assert!(columns_match_struct::<Testing>(&matching).is_ok());
+
+        let mismatched = result_set(&[("id", "varchar"), ("note", "varchar")], &[]);
+        let mismatch = columns_match_struct::<Testing>(&mismatched).unwrap_err();
+        assert_eq!(
+            mismatch,
+            Mismatch {
+                missing: vec!["name".to_string()],
+                type_conflicts: vec![("id".to_string(), "varchar".to_string(), "i64".to_string())],
+            }
+        );
+        assert_eq!(
+            mismatch.to_string(),
+            "Result set does not match the struct. Missing columns: `name`. `id` is `varchar`, which cannot be parsed into `i64`."
+        );
+    }
+
+    #[test]
+    fn parameterized_types_match_their_base_type() {
+        assert_eq!(base_type("decimal(10,2)"), "decimal");
+        assert_eq!(base_type("VARCHAR(20)"), "varchar");
+        assert_eq!(base_type("varbinary"), "varbinary");
+
+        let parameterized = result_set(&[("id", "decimal(10,0)"), ("name", "varchar(20)")], &[]);
+        assert!(compatible("decimal(10,2)", "f64"));
+        assert!(!compatible("decimal(10,2)", "i64"));
+        assert_eq!(
+            columns_match_struct::<Testing>(&parameterized)
+                .unwrap_err()
+                .type_conflicts,
+            vec![(
+                "id".to_string(),
+                "decimal(10,0)".to_string(),
+                "i64".to_string()
+            )]
+        );
+    }
+}
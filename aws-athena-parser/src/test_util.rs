@@ -0,0 +1,211 @@
+//! Helpers for building `ResultSet`s in tests.
+//!
+//! These are available to downstream crates through the `test-util` feature, making it
+//! easy to test `FromAthena` implementations without an Athena response at hand.
+
+use aws_sdk_athena::types::{ColumnInfo, Datum, ResultSet, ResultSetMetadata, Row};
+
+/// Builds a ResultSet from `(name, type)` column pairs and rows of values.
+///
+/// Each row lists its values in column order.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::test_util::result_set;
+///
+/// let result_set = result_set(
+///     &[("id", "bigint"), ("name", "varchar")],
+///     &[&["1", "first"], &["2", "second"]],
+/// );
+/// ```
+pub fn result_set(columns: &[(&str, &str)], rows: &[&[&str]]) -> ResultSet {
+    let rows: Vec<Vec<Option<&str>>> = rows
+        .iter()
+        .map(|row| row.iter().map(|v| Some(*v)).collect())
+        .collect();
+    let rows: Vec<&[Option<&str>]> = rows.iter().map(Vec::as_slice).collect();
+
+    result_set_with_nulls(columns, &rows)
+}
+
+/// Builds a ResultSet from `(name, type)` column pairs and rows of values.
+///
+/// This is another name for [`result_set`].
+pub fn mock_result_set(columns: &[(&str, &str)], rows: &[&[&str]]) -> ResultSet {
+    result_set(columns, rows)
+}
+
+/// Builds a ResultSet as [`result_set`] does, where `None` values are SQL nulls.
+///
+/// A SQL null is a `Datum` without a `var_char_value`, as returned by Athena.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::test_util::result_set_with_nulls;
+///
+/// let result_set = result_set_with_nulls(
+///     &[("id", "bigint"), ("name", "varchar")],
+///     &[&[Some("1"), None]],
+/// );
+/// ```
+pub fn result_set_with_nulls(columns: &[(&str, &str)], rows: &[&[Option<&str>]]) -> ResultSet {
+    let columns = columns.iter().map(|(name, ty)| column(name, ty)).collect();
+    let metadata = ResultSetMetadata::builder()
+        .set_column_info(Some(columns))
+        .build();
+
+    let rows = rows
+        .iter()
+        .map(|values| {
+            let data = values
+                .iter()
+                .map(|v| {
+                    Datum::builder()
+                        .set_var_char_value(v.map(str::to_string))
+                        .build()
+                })
+                .collect();
+            Row::builder().set_data(Some(data)).build()
+        })
+        .collect();
+
+    ResultSet::builder()
+        .result_set_metadata(metadata)
+        .set_rows(Some(rows))
+        .build()
+}
+
+/// Builds a ResultSet from a `GetQueryResults` response serialized as AWS returns it.
+///
+/// This allows capturing a real response once, for example with
+/// `aws athena get-query-results`, and parsing it offline. Either the whole response or
+/// only its `ResultSet` object is accepted. Only the `Name` and `Type` of each column are
+/// read, and a `Datum` without a `VarCharValue` is a SQL null.
+///
+/// # Errors
+///
+/// Returns an error if the reader does not hold valid JSON, or the JSON lacks the column
+/// metadata or rows of a `ResultSet`.
+///
+/// # Examples
+///
+/// ```
+/// use aws_athena_parser::test_util::from_json_fixture;
+///
+/// let json = r#"{"ResultSet": {
+///     "Rows": [{"Data": [{"VarCharValue": "id"}]}, {"Data": [{"VarCharValue": "1"}]}],
+///     "ResultSetMetadata": {"ColumnInfo": [{"Name": "id", "Type": "bigint"}]}
+/// }}"#;
+///
+/// let result_set = from_json_fixture(json.as_bytes()).unwrap();
+/// assert_eq!(result_set.rows().len(), 2);
+/// ```
+#[cfg(feature = "test-util")]
+pub fn from_json_fixture(reader: impl std::io::Read) -> anyhow::Result<ResultSet> {
+    use serde_json::Value;
+
+    let response: Value = serde_json::from_reader(reader)?;
+    let result_set = response.get("ResultSet").unwrap_or(&response);
+
+    let invalid = |what: &str| anyhow::Error::msg(format!("Invalid fixture. {}!", what));
+
+    let columns = result_set
+        .pointer("/ResultSetMetadata/ColumnInfo")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("`ResultSetMetadata.ColumnInfo` was not found"))?
+        .iter()
+        .map(|info| {
+            let name = info.get("Name").and_then(Value::as_str);
+            let ty = info.get("Type").and_then(Value::as_str);
+            match (name, ty) {
+                (Some(name), Some(ty)) => Ok(column(name, ty)),
+                _ => Err(invalid("every column needs a `Name` and `Type`")),
+            }
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let rows = result_set
+        .get("Rows")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("`Rows` was not found"))?
+        .iter()
+        .map(|row| {
+            let data = row
+                .get("Data")
+                .and_then(Value::as_array)
+                .ok_or_else(|| invalid("every row needs `Data`"))?
+                .iter()
+                .map(|datum| {
+                    Datum::builder()
+                        .set_var_char_value(
+                            datum
+                                .get("VarCharValue")
+                                .and_then(Value::as_str)
+                                .map(str::to_string),
+                        )
+                        .build()
+                })
+                .collect();
+            Ok(Row::builder().set_data(Some(data)).build())
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let metadata = ResultSetMetadata::builder()
+        .set_column_info(Some(columns))
+        .build();
+
+    Ok(ResultSet::builder()
+        .result_set_metadata(metadata)
+        .set_rows(Some(rows))
+        .build())
+}
+
+/// Builds the `ColumnInfo` of a column with the given name and Athena type.
+pub fn column(name: &str, ty: &str) -> ColumnInfo {
+    ColumnInfo::builder()
+        .name(name)
+        .r#type(ty)
+        .build()
+        .expect("name and type are set")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mock_result_set_builds_metadata_and_rows() {
+        let result_set = mock_result_set(
+            &[("id", "bigint"), ("name", "varchar")],
+            &[&["1", "first"], &["2", "second"]],
+        );
+
+        let columns = result_set.result_set_metadata().unwrap().column_info();
+        assert_eq!(columns[0].name(), "id");
+        assert_eq!(columns[1].r#type(), "varchar");
+
+        let rows = result_set.rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].data()[1].var_char_value(), Some("second"));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn parse_json_fixture() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/get_query_results.json"
+        );
+        let result_set = from_json_fixture(std::fs::File::open(path).unwrap()).unwrap();
+
+        // The first page of a response starts with a header row.
+        let rows = crate::build_map(result_set);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["name"], "name");
+        assert_eq!(rows[1]["name"], "first");
+        assert_eq!(rows[2]["id"], "2");
+        assert!(!rows[2].contains_key("note"));
+    }
+}
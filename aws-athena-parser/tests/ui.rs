@@ -0,0 +1,26 @@
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+    t.pass("tests/ui/pass/*.rs");
+
+    // The diagnostic lists other `FromStr` types, which differ between toolchains and grow
+    // with the types optional features add, such as `Uuid` or `BigDecimal`. The expected
+    // output was recorded with rustc 1.95.0, so this only runs with the default features.
+    #[cfg(not(any(
+        feature = "client",
+        feature = "stream",
+        feature = "test-util",
+        feature = "json",
+        feature = "uuid",
+        feature = "system-time",
+        feature = "chrono",
+        feature = "chrono-tz",
+        feature = "geo",
+        feature = "url",
+        feature = "bigdecimal",
+        feature = "ordered",
+        feature = "csv",
+    )))]
+    t.compile_fail("tests/ui/unsupported/*.rs");
+}
@@ -0,0 +1,9 @@
+use aws_athena_parser::FromAthena;
+
+#[derive(FromAthena)]
+struct Testing {
+    #[athena(hex, epoch = "millis")]
+    pub data: Vec<u8>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use aws_athena_parser::FromAthena;
+
+#[derive(FromAthena)]
+struct Testing {
+    #[athena(decimal_separator = ',', thousands_separator = ',')]
+    pub value: f64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use aws_athena_parser::FromAthena;
+
+#[derive(FromAthena)]
+struct Testing {
+    #[athena(bool_from_int, bool_from_int)]
+    pub value: bool,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use aws_athena_parser::FromAthena;
+
+#[derive(FromAthena)]
+struct Testing {
+    #[athena(percent = "ratio")]
+    pub value: f64,
+}
+
+fn main() {}
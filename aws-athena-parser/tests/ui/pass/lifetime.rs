@@ -0,0 +1,32 @@
+use aws_athena_parser::{FromAthena, HashMap, ToAthena};
+use std::borrow::Cow;
+
+mod cow {
+    use std::borrow::Cow;
+
+    pub fn from_athena_field<'a>(value: &str) -> aws_athena_parser::anyhow::Result<Cow<'a, str>> {
+        Ok(Cow::Owned(value.to_string()))
+    }
+
+    pub fn to_athena_field(value: &Cow<'_, str>) -> String {
+        value.to_string()
+    }
+}
+
+#[derive(FromAthena, ToAthena)]
+struct Testing<'a> {
+    pub id: i64,
+    #[athena(with = "cow")]
+    pub name: Cow<'a, str>,
+}
+
+fn main() {
+    let row = HashMap::from([
+        ("id".to_string(), "1".to_string()),
+        ("name".to_string(), "test".to_string()),
+    ]);
+
+    let parsed = Testing::from_athena(row.clone()).unwrap();
+    assert_eq!(parsed.id, 1);
+    assert_eq!(parsed.to_athena(), row);
+}
@@ -0,0 +1,11 @@
+use aws_athena_parser::{anyhow, FromAthena, HashMap};
+
+struct Unsupported;
+
+#[derive(FromAthena)]
+struct MyStruct {
+    id: i64,
+    value: Unsupported,
+}
+
+fn main() {}
@@ -0,0 +1,547 @@
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, Field, LitChar, LitInt, LitStr, Path};
+
+/// The keys accepted within a struct's `#[athena(...)]` attribute.
+const CONTAINER_KEYS: &[&str] = &["error_prefix", "default_on_empty_row", "lenient"];
+
+/// The keys accepted within a field's `#[athena(...)]` attribute.
+const FIELD_KEYS: &[&str] = &[
+    "percent",
+    "bool_from_int",
+    "hex",
+    "epoch",
+    "wkt",
+    "position",
+    "trim_matches",
+    "with",
+    "delimiter",
+    "flatten",
+    "array_delim",
+    "map_entry_delim",
+    "map_kv_delim",
+    "rename",
+    "flatten_delimited",
+    "decimal_separator",
+    "thousands_separator",
+    "null_token",
+    "formats",
+    "bytes_encoding",
+    "duration_unit",
+    "collect_prefix",
+    "prefix",
+    "min",
+    "max",
+    "normalize_zero",
+];
+
+/// The keys accepted within an enum variant's `#[athena(...)]` attribute.
+const VARIANT_KEYS: &[&str] = &["rename"];
+
+/// A numeric literal given to `#[athena(min = ...)]` or `#[athena(max = ...)]`, such as `-5`
+/// or `1.5`.
+pub struct Bound {
+    pub minus: Option<syn::Token![-]>,
+    pub lit: syn::Lit,
+}
+
+impl quote::ToTokens for Bound {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.minus.to_tokens(tokens);
+        self.lit.to_tokens(tokens);
+    }
+}
+
+/// How the value of a `#[athena(percent)]` field is interpreted.
+pub enum Percent {
+    /// `42%` becomes `42.0`, for `percent = "raw"`.
+    Raw,
+    /// `42%` becomes `0.42`, for a bare `percent` or `percent = "fraction"`.
+    Fraction,
+}
+
+/// The unit of the integer read by a `#[athena(epoch = "...")]` field.
+pub enum Epoch {
+    /// Seconds since the UNIX epoch.
+    Seconds,
+    /// Milliseconds since the UNIX epoch.
+    Millis,
+}
+
+/// The unit of the number read by a `std::time::Duration` field.
+pub enum DurationUnit {
+    /// Seconds, the default.
+    Seconds,
+    /// Milliseconds, set with `#[athena(duration_unit = "millis")]`.
+    Millis,
+}
+
+/// How a `#[athena(bytes_encoding = "...")]` field decodes its `Vec<u8>`.
+pub enum BytesEncoding {
+    /// Standard or URL safe base64.
+    Base64,
+    /// Hex pairs such as `deadbeef`.
+    Hex,
+    /// The UTF-8 bytes of the value as is.
+    Utf8,
+}
+
+/// A `#[serde(rename_all = "...")]` case convention, applied to the snake_case field names.
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    /// Returns the rule with the name serde gives it, such as `camelCase`.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "lowercase" => Some(RenameRule::Lower),
+            "UPPERCASE" => Some(RenameRule::Upper),
+            "PascalCase" => Some(RenameRule::Pascal),
+            "camelCase" => Some(RenameRule::Camel),
+            "snake_case" => Some(RenameRule::Snake),
+            "SCREAMING_SNAKE_CASE" => Some(RenameRule::ScreamingSnake),
+            "kebab-case" => Some(RenameRule::Kebab),
+            "SCREAMING-KEBAB-CASE" => Some(RenameRule::ScreamingKebab),
+            _ => None,
+        }
+    }
+
+    /// Renames a snake_case field name as serde would.
+    pub fn apply(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => field
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.apply(field);
+                let mut chars = pascal.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
+
+/// Options collected from the `#[athena(...)]` attributes of the struct itself.
+#[derive(Default)]
+pub struct ContainerAttrs {
+    pub error_prefix: Option<LitStr>,
+    pub default_on_empty_row: bool,
+    pub lenient: bool,
+    /// Read from `#[serde(rename_all = "...")]`, which has no `athena` equivalent.
+    pub rename_all: Option<RenameRule>,
+}
+
+impl ContainerAttrs {
+    /// Collects the `#[athena(...)]` attributes placed on the struct.
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut container = ContainerAttrs::default();
+        let mut seen = Vec::new();
+
+        for attr in athena_attrs(attrs) {
+            attr.parse_nested_meta(|meta| {
+                let key = key_of(&meta, CONTAINER_KEYS)?;
+                if seen.contains(&key) {
+                    return Err(meta.error(format!("duplicate `athena` attribute `{}`", key)));
+                }
+                seen.push(key.clone());
+
+                match key.as_str() {
+                    "error_prefix" => container.error_prefix = Some(meta.value()?.parse()?),
+                    "default_on_empty_row" => container.default_on_empty_row = true,
+                    "lenient" => container.lenient = true,
+                    _ => unreachable!("`key_of` only returns supported keys"),
+                }
+
+                Ok(())
+            })?;
+        }
+
+        for attr in serde_attrs(attrs) {
+            // Serde reports its own malformed attributes, so anything unreadable is ignored.
+            let _ = attr.parse_nested_meta(|meta| {
+                match serde_rename(&meta, "rename_all")? {
+                    Some(rule) => container.rename_all = RenameRule::from_name(&rule.value()),
+                    None => skip_serde_meta(&meta)?,
+                }
+                Ok(())
+            });
+        }
+
+        Ok(container)
+    }
+}
+
+/// Options collected from the `#[athena(...)]` attributes of a single field.
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub percent: Option<Percent>,
+    pub bool_from_int: bool,
+    pub hex: bool,
+    pub wkt: bool,
+    pub epoch: Option<Epoch>,
+    pub position: Option<LitInt>,
+    pub trim_matches: Option<LitStr>,
+    pub with: Option<Path>,
+    pub delimiter: Option<LitStr>,
+    pub flatten: bool,
+    pub array_delim: Option<LitStr>,
+    pub map_entry_delim: Option<LitStr>,
+    pub map_kv_delim: Option<LitStr>,
+    pub rename: Option<LitStr>,
+    pub flatten_delimited: Option<LitStr>,
+    pub decimal_separator: Option<LitChar>,
+    pub thousands_separator: Option<LitChar>,
+    pub null_token: Option<LitStr>,
+    pub formats: Option<Vec<LitStr>>,
+    pub bytes_encoding: Option<BytesEncoding>,
+    pub duration_unit: Option<DurationUnit>,
+    pub collect_prefix: Option<LitStr>,
+    pub prefix: Option<LitStr>,
+    pub min: Option<Bound>,
+    pub max: Option<Bound>,
+    pub normalize_zero: bool,
+}
+
+impl FieldAttrs {
+    /// Collects the `#[athena(...)]` attributes of a field.
+    ///
+    /// Unknown, repeated or conflicting keys are rejected with an error pointing at the
+    /// offending key.
+    pub fn from_field(field: &Field) -> syn::Result<Self> {
+        let mut attrs = FieldAttrs::default();
+        let mut seen = Vec::new();
+
+        for attr in athena_attrs(&field.attrs) {
+            attr.parse_nested_meta(|meta| {
+                let key = key_of(&meta, FIELD_KEYS)?;
+                if seen.contains(&key) {
+                    return Err(meta.error(format!("duplicate `athena` attribute `{}`", key)));
+                }
+                seen.push(key.clone());
+
+                match key.as_str() {
+                    "percent" => {
+                        attrs.percent = if meta.input.peek(syn::Token![=]) {
+                            let mode: LitStr = meta.value()?.parse()?;
+                            match mode.value().as_str() {
+                                "raw" => Some(Percent::Raw),
+                                "fraction" => Some(Percent::Fraction),
+                                _ => {
+                                    return Err(syn::Error::new(
+                                        mode.span(),
+                                        "expected `percent = \"raw\"` or `percent = \"fraction\"`",
+                                    ))
+                                }
+                            }
+                        } else {
+                            Some(Percent::Fraction)
+                        };
+                    }
+                    "bool_from_int" => attrs.bool_from_int = true,
+                    "hex" => attrs.hex = true,
+                    "wkt" => attrs.wkt = true,
+                    "normalize_zero" => attrs.normalize_zero = true,
+                    "trim_matches" => attrs.trim_matches = Some(meta.value()?.parse()?),
+                    "flatten" => attrs.flatten = true,
+                    "array_delim" => attrs.array_delim = Some(meta.value()?.parse()?),
+                    "map_entry_delim" => attrs.map_entry_delim = Some(meta.value()?.parse()?),
+                    "map_kv_delim" => attrs.map_kv_delim = Some(meta.value()?.parse()?),
+                    "delimiter" => attrs.delimiter = Some(meta.value()?.parse()?),
+                    "rename" => attrs.rename = Some(meta.value()?.parse()?),
+                    "null_token" => attrs.null_token = Some(meta.value()?.parse()?),
+                    "collect_prefix" => attrs.collect_prefix = Some(meta.value()?.parse()?),
+                    "prefix" => attrs.prefix = Some(meta.value()?.parse()?),
+                    "min" => attrs.min = Some(parse_bound(&meta)?),
+                    "max" => attrs.max = Some(parse_bound(&meta)?),
+                    "formats" => {
+                        let input = meta.value()?;
+                        let content;
+                        let brackets = syn::bracketed!(content in input);
+                        let formats =
+                            content.parse_terminated(<LitStr as syn::parse::Parse>::parse, syn::Token![,])?;
+                        if formats.is_empty() {
+                            return Err(syn::Error::new(
+                                brackets.span.join(),
+                                "expected at least one format such as `\"%Y-%m-%d\"`",
+                            ));
+                        }
+                        attrs.formats = Some(formats.into_iter().collect());
+                    }
+                    "decimal_separator" => {
+                        attrs.decimal_separator = Some(meta.value()?.parse()?)
+                    }
+                    "thousands_separator" => {
+                        attrs.thousands_separator = Some(meta.value()?.parse()?)
+                    }
+                    "flatten_delimited" => {
+                        attrs.flatten_delimited = Some(meta.value()?.parse()?)
+                    }
+                    "with" => {
+                        let module: LitStr = meta.value()?.parse()?;
+                        attrs.with = Some(module.parse()?);
+                    }
+                    "position" => {
+                        let position: LitInt = meta.value()?.parse()?;
+                        position.base10_parse::<usize>()?;
+                        attrs.position = Some(position);
+                    }
+                    "bytes_encoding" => {
+                        let encoding: LitStr = meta.value()?.parse()?;
+                        attrs.bytes_encoding = match encoding.value().as_str() {
+                            "base64" => Some(BytesEncoding::Base64),
+                            "hex" => Some(BytesEncoding::Hex),
+                            "utf8" => Some(BytesEncoding::Utf8),
+                            _ => {
+                                return Err(syn::Error::new(
+                                    encoding.span(),
+                                    "expected `bytes_encoding = \"base64\"`, `\"hex\"` or `\"utf8\"`",
+                                ))
+                            }
+                        };
+                    }
+                    "duration_unit" => {
+                        let unit: LitStr = meta.value()?.parse()?;
+                        attrs.duration_unit = match unit.value().as_str() {
+                            "seconds" => Some(DurationUnit::Seconds),
+                            "millis" => Some(DurationUnit::Millis),
+                            _ => {
+                                return Err(syn::Error::new(
+                                    unit.span(),
+                                    "expected `duration_unit = \"seconds\"` or `duration_unit = \"millis\"`",
+                                ))
+                            }
+                        };
+                    }
+                    "epoch" => {
+                        let unit: LitStr = meta.value()?.parse()?;
+                        attrs.epoch = match unit.value().as_str() {
+                            "seconds" => Some(Epoch::Seconds),
+                            "millis" => Some(Epoch::Millis),
+                            _ => {
+                                return Err(syn::Error::new(
+                                    unit.span(),
+                                    "expected `epoch = \"seconds\"` or `epoch = \"millis\"`",
+                                ))
+                            }
+                        };
+                    }
+                    _ => unreachable!("`key_of` only returns supported keys"),
+                }
+
+                let set = [
+                    attrs.percent.is_some(),
+                    attrs.bool_from_int,
+                    attrs.hex,
+                    attrs.wkt,
+                    attrs.epoch.is_some(),
+                    attrs.with.is_some(),
+                    attrs.decimal_separator.is_some() || attrs.thousands_separator.is_some(),
+                    attrs.formats.is_some(),
+                    attrs.bytes_encoding.is_some(),
+                ];
+                if set.iter().filter(|set| **set).count() > 1 {
+                    return Err(meta.error(
+                        "only one of `percent`, `bool_from_int`, `hex`, `wkt`, `epoch`, `with`, `decimal_separator`, `formats` and `bytes_encoding` can be used",
+                    ));
+                }
+
+                if attrs.collect_prefix.is_some()
+                    && (attrs.flatten
+                        || attrs.flatten_delimited.is_some()
+                        || attrs.rename.is_some()
+                        || attrs.position.is_some())
+                {
+                    return Err(meta.error(
+                        "`collect_prefix` cannot be used with `flatten`, `flatten_delimited`, `rename` or `position`",
+                    ));
+                }
+
+                if attrs.prefix.is_some()
+                    && (attrs.flatten
+                        || attrs.flatten_delimited.is_some()
+                        || attrs.collect_prefix.is_some()
+                        || attrs.rename.is_some()
+                        || attrs.position.is_some())
+                {
+                    return Err(meta.error(
+                        "`prefix` cannot be used with `flatten`, `flatten_delimited`, `collect_prefix`, `rename` or `position`",
+                    ));
+                }
+
+                let decimal = attrs.decimal_separator.as_ref().map_or('.', LitChar::value);
+                if attrs
+                    .thousands_separator
+                    .as_ref()
+                    .is_some_and(|thousands| thousands.value() == decimal)
+                {
+                    return Err(meta.error(format!(
+                        "`{}` cannot be both the decimal and the thousands separator",
+                        decimal
+                    )));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        if attrs.rename.is_none() {
+            for attr in serde_attrs(&field.attrs) {
+                let _ = attr.parse_nested_meta(|meta| {
+                    match serde_rename(&meta, "rename")? {
+                        Some(rename) => attrs.rename = Some(rename),
+                        None => skip_serde_meta(&meta)?,
+                    }
+                    Ok(())
+                });
+            }
+        }
+
+        Ok(attrs)
+    }
+}
+
+impl FieldAttrs {
+    /// Returns whether an attribute, rather than the field type, decides how the value is
+    /// parsed.
+    pub fn decides_parsing(&self) -> bool {
+        self.percent.is_some()
+            || self.bool_from_int
+            || self.hex
+            || self.wkt
+            || self.epoch.is_some()
+            || self.with.is_some()
+            || self.trim_matches.is_some()
+            || self.decimal_separator.is_some()
+            || self.thousands_separator.is_some()
+            || self.formats.is_some()
+            || self.bytes_encoding.is_some()
+    }
+}
+
+/// Reads the `#[athena(rename = "...")]` attribute of an enum variant.
+pub fn variant_rename(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let mut rename = None;
+
+    for attr in athena_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            key_of(&meta, VARIANT_KEYS)?;
+            if rename.is_some() {
+                return Err(meta.error("duplicate `athena` attribute `rename`"));
+            }
+            rename = Some(meta.value()?.parse()?);
+            Ok(())
+        })?;
+    }
+
+    Ok(rename)
+}
+
+/// Returns the `#[athena(...)]` attributes from a list of attributes.
+fn athena_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|a| a.path().is_ident("athena"))
+}
+
+/// Reads the numeric literal of a `min` or `max` key, optionally negated.
+fn parse_bound(meta: &ParseNestedMeta) -> syn::Result<Bound> {
+    let input = meta.value()?;
+    let minus = input.parse()?;
+    let lit: syn::Lit = input.parse()?;
+
+    match lit {
+        syn::Lit::Int(_) | syn::Lit::Float(_) => Ok(Bound { minus, lit }),
+        _ => Err(syn::Error::new(
+            lit.span(),
+            "expected a number such as `0` or `1.5`",
+        )),
+    }
+}
+
+/// Returns the `#[serde(...)]` attributes from a list of attributes.
+fn serde_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|a| a.path().is_ident("serde"))
+}
+
+/// Reads a serde `key = "..."` or `key(deserialize = "...")` item, returning `None` for
+/// any other item.
+fn serde_rename(meta: &ParseNestedMeta, key: &str) -> syn::Result<Option<LitStr>> {
+    if !meta.path.is_ident(key) {
+        return Ok(None);
+    }
+    if meta.input.peek(syn::Token![=]) {
+        return Ok(Some(meta.value()?.parse()?));
+    }
+
+    let mut rename = None;
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("deserialize") {
+            rename = Some(nested.value()?.parse()?);
+        } else {
+            skip_serde_meta(&nested)?;
+        }
+        Ok(())
+    })?;
+    Ok(rename)
+}
+
+/// Consumes a serde item this crate does not read, such as `default` or `with = "..."`.
+fn skip_serde_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Lit>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|nested| skip_serde_meta(&nested))?;
+    }
+    Ok(())
+}
+
+/// Returns the key of a nested meta item if it is one of `supported`.
+///
+/// Otherwise an error pointing at the key and listing the supported keys is returned.
+fn key_of(meta: &ParseNestedMeta, supported: &[&str]) -> syn::Result<String> {
+    let key = meta
+        .path
+        .get_ident()
+        .map(|ident| ident.to_string())
+        .filter(|key| supported.contains(&key.as_str()));
+
+    key.ok_or_else(|| {
+        let expected = supported
+            .iter()
+            .map(|key| format!("`{}`", key))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let path = meta
+            .path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+
+        meta.error(format!(
+            "unknown `athena` attribute `{}`, expected one of {}",
+            path, expected
+        ))
+    })
+}
@@ -1,8 +1,16 @@
 extern crate proc_macro;
 
+mod attr;
+mod to_athena;
+mod unit_enum;
+
+use attr::{BytesEncoding, ContainerAttrs, DurationUnit, Epoch, FieldAttrs, Percent};
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Fields};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, DeriveInput, Fields, GenericArgument, PathArguments, Type};
 
 /// Converts data from an Athena query result into a struct implementing the `FromAthena` trait.
 ///
@@ -20,48 +28,1057 @@ use syn::{parse_macro_input, DeriveInput, Fields};
 ///
 /// # Examples
 ///
-/// ```
-/// use aws_athena_parser::{from_athena, FromAthena};
+/// ```ignore
+/// use aws_athena_parser::FromAthena;
 ///
 /// #[derive(FromAthena)]
 /// struct MyStruct {
 ///     field1: String,
 ///     field2: i32,
+///     #[athena(percent = "fraction")]
+///     field3: f64,
 /// }
 /// ```
-#[proc_macro_derive(FromAthena)]
+///
+/// Integer fields report the field, the value and whether it overflowed when they fail to parse.
+///
+/// `Option<T>` fields are `None` when the row has no value for the column, and otherwise
+/// parse the value as a `T` field would. Columns Athena reports with the `unknown` type,
+/// such as `SELECT NULL AS x`, never have a value.
+///
+/// `HashMap<K, V>` and `BTreeMap<K, V>` fields are parsed from Athena's map representation
+/// (`{a=1, b=2}`), with or without the braces.
+///
+/// Tuple fields such as `(i64, i64)` are split on `,`, or the `delimiter` attribute, and each
+/// element is parsed as a field of its type would be.
+///
+/// `Vec<T>`, `HashSet<T>` and `BTreeSet<T>` fields are parsed from Athena's array
+/// representation (`[a, b, c]`) or a plain comma separated list (`a,b,c`).
+///
+/// Collections of `Option<T>`, such as `Vec<Option<i64>>`, read `null` elements as `None`.
+/// A `null` element within a collection of any other element type is an error.
+///
+/// `Vec<u8>` fields are decoded from Athena's hex `varbinary` representation. Base64 values
+/// require `#[athena(bytes_encoding = "base64")]`, as they cannot be told apart from hex.
+///
+/// `Uuid` fields are parsed with `uuid::Uuid::parse_str` and require the `uuid` feature.
+///
+/// `DateTime<Tz>` fields, zoned by `chrono_tz::Tz`, are parsed from a timestamp followed by an
+/// IANA zone name and require the `chrono-tz` feature. Ambiguous local times resolve to the
+/// earliest instant.
+///
+/// `Url` fields are parsed with `url::Url::parse`, accepting only absolute URLs, and require
+/// the `url` feature.
+///
+/// `BigDecimal` fields are parsed from `decimal` or `double` values without losing precision
+/// and require the `bigdecimal` feature.
+///
+/// Integer and floating point fields accept a leading `+`, so `+5` reads as `5`. An integer
+/// field reads `-0` as `0`, while a floating point field keeps the sign of `-0.0`, which
+/// compares equal to `0.0` but prints as `-0`. Use `#[athena(normalize_zero)]` to read it as
+/// `0.0`.
+///
+/// `String` fields take the value as is, without going through `str::parse`.
+///
+/// `Duration` fields, from `std::time`, are parsed from a non-negative number of seconds such
+/// as `90` or `1.5`.
+///
+/// `char` fields read a single Unicode character, such as `€`, and reject longer values.
+///
+/// `PathBuf` fields, such as S3 keys, are built from the value with `PathBuf::from`.
+///
+/// `SystemTime` fields are parsed from epoch milliseconds or a UTC Athena timestamp and
+/// require the `system-time` feature.
+///
+/// The types above are recognised by how the field spells them, as the derive cannot see
+/// imports. A path naming another crate, such as `chrono::Duration`, is parsed through
+/// `FromStr` instead, but a bare `Duration`, `Uuid` or `Url` is always taken for the type
+/// listed here, even when it was imported from elsewhere. Spell out the path of such a
+/// type, or parse it with `#[athena(with = "...")]`.
+///
+/// # Attributes
+///
+/// Unknown or conflicting keys within `#[athena(...)]` are reported as compile errors.
+///
+/// Errors returned by the generated `from_athena` carry the struct name as context over the
+/// underlying error, so formatting them with `{:#}` gives for example
+/// ``MyStruct: Missing field within result set. `field1` was not found!``.
+///
+/// * `#[athena(error_prefix = "...")]` - On the struct, replaces the struct name given as error context.
+/// * `#[athena(default_on_empty_row)]` - On the struct, returns `Default::default()` when every
+///   column read by the struct is missing or empty, as for the null side of an outer join.
+/// * `#[athena(lenient)]` - On the struct, also generates
+///   `from_athena_lenient(row) -> (Self, Vec<FieldError>)`, where a field that fails to parse
+///   falls back to `Default::default()` and is reported in the returned list, with its error
+///   prefixed as for `from_athena`. `from_athena_lenient_with_types(&row, &types)` also takes
+///   the Athena type of each column, as `from_athena_with_types` does. Every field type must
+///   implement `Default`.
+/// * `#[athena(percent)]` - Strips a trailing `%` and parses the value into an `f64` divided by
+///   100, so `98.5%` becomes `0.985`. `#[athena(percent = "fraction")]` spells out the same
+///   behaviour.
+/// * `#[athena(percent = "raw")]` - As `percent`, but keeps the number as is, so `98.5%` becomes
+///   `98.5`.
+/// * `#[athena(bool_from_int)]` - Parses a `bool` from `0` or `1`, rejecting any other value.
+/// * `#[athena(epoch = "seconds")]` - Parses a `chrono::DateTime<Utc>` from an integer count of
+///   seconds since the UNIX epoch. Requires the `chrono` feature.
+/// * `#[athena(epoch = "millis")]` - As `epoch = "seconds"`, but reads milliseconds.
+/// * `#[athena(formats = ["%Y-%m-%d", "%m/%d/%Y"])]` - Parses a `chrono::NaiveDate`,
+///   `NaiveDateTime` or `NaiveTime` with each format in turn, keeping the first that succeeds.
+///   Requires the `chrono` feature.
+/// * `#[athena(with = "module")]` - Parses the value with `module::from_athena_field`, a function
+///   taking the `&str` value and returning `anyhow::Result<T>` for the field type `T`.
+/// * `#[athena(trim_matches = "...")]` - Strips any of the given characters from both ends of the
+///   value before it is parsed, so `trim_matches = "\""` reads `"value"` as `value`. Each end is
+///   stripped on its own, so a quote on only one side is stripped as well.
+/// * `#[athena(flatten)]` - Collects every column not read by another field. A
+///   `HashMap<String, String>` field receives the raw values, while a
+///   `serde_json::Map<String, Value>` field receives values converted according to their column
+///   type, which requires the `json` feature. A field of any other type is parsed from the
+///   same row with its own `FromAthena` implementation, so structs deriving `FromAthena` can
+///   be composed. A column read by several flattened structs is read by each of them, and
+///   `aws_athena_parser::overlapping_columns` lists such columns.
+/// * `#[athena(array_delim = "...")]` - Splits a `Vec`, `HashSet` or `BTreeSet` field on the given
+///   string instead of `,`.
+/// * `#[athena(null_token = "...")]` - Reads the given element as `None` within a collection of
+///   `Option<T>`, instead of `null`.
+/// * `#[athena(map_entry_delim = "...", map_kv_delim = "...")]` - Splits the entries of a map
+///   field on `map_entry_delim` instead of `,`, and each key from its value on `map_kv_delim`
+///   instead of `=`.
+/// * `#[athena(delimiter = "...")]` - Splits a tuple field on the given string instead of `,`.
+/// * `#[athena(flatten_delimited = "...")]` - Reads part of the named column instead of the
+///   column named after the field. The column is split on `,`, or the `delimiter` set on any
+///   field of the group, and the fields naming the same column receive consecutive parts in
+///   declaration order, the last one keeping any remaining delimiters. A value with fewer parts
+///   than fields is an error.
+/// * `#[athena(decimal_separator = ',')]` - Parses an `f32` or `f64` written with the given
+///   decimal separator, such as `3,14`.
+/// * `#[athena(thousands_separator = '.')]` - Ignores the given grouping separator when parsing an
+///   `f32` or `f64`, such as `1.234,5` with `decimal_separator = ','`. The two separators must
+///   differ.
+/// * `#[athena(rename = "...")]` - Reads the column with the given name instead of the column
+///   named after the field, for columns such as `user-id` that are not valid Rust identifiers.
+///   Without it, `#[serde(rename = "...")]` is read instead, and `#[serde(rename_all = "...")]`
+///   on the struct renames the remaining fields, so a struct mapped for serde maps the same
+///   columns. Other `serde` keys are ignored.
+/// * `#[athena(position = N)]` - Reads the column at index `N` instead of the column named after
+///   the field, for unnamed columns such as `_col0`. The value is read from the row itself, so
+///   the column may share its name with another column. Only applies when parsing a
+///   `ResultSet`: `from_athena` and the other conversions of an already mapped row ignore
+///   `position` and read the column named after the field.
+/// * `#[athena(duration_unit = "millis")]` - Reads a `std::time::Duration` field as milliseconds
+///   instead of seconds. `#[athena(duration_unit = "seconds")]` spells out the default.
+/// * `#[athena(collect_prefix = "...")]` - Collects the columns named after the given prefix and
+///   a number, such as `score_1`, `score_2` and `score_10`, into a `Vec<T>` field ordered by that
+///   number. Gaps in the numbering are skipped, columns whose name continues with anything
+///   other than digits are ignored, and a row without any such column gives an empty `Vec`.
+///   The columns are not listed by `athena_columns`, so a `flatten` field also receives them.
+/// * `#[athena(prefix = "...")]` - Collects every column whose name starts with the given prefix
+///   into a `HashMap<K, V>` or `BTreeMap<K, V>` field, keyed by the rest of the column name.
+///   For pivoted columns such as `metric_2020` and `metric_2021`, `prefix = "metric_"` gives
+///   the keys `2020` and `2021`, parsed as `K`. As with `collect_prefix`, the columns are not
+///   listed by `athena_columns`.
+/// * `#[athena(min = N, max = N)]` - Fails the row when the parsed value is below `min` or above
+///   `max`, both inclusive, naming the column and the bound. Either bound may be given alone,
+///   leaving the other side open. The bounds are literals of the field type, so an `f64` field
+///   takes `min = 0.0` rather than `min = 0`. An `Option<T>` field is only checked when it
+///   has a value.
+/// * `#[athena(normalize_zero)]` - Reads `-0.0` into an `f32` or `f64` field as `0.0`, so the
+///   value does not carry a negative sign into formatting or `is_sign_negative` checks.
+/// * `#[athena(wkt)]` - Parses a `geo_types::Geometry` from WKT such as `POINT (1 2)`. Requires
+///   the `geo` feature.
+/// * `#[athena(hex)]` - Decodes a `Vec<u8>` from a hex string such as `deadbeef`, ignoring any
+///   whitespace rather than expecting Athena's space separated pairs.
+/// * `#[athena(bytes_encoding = "...")]` - Decodes a `Vec<u8>` with the given encoding instead of
+///   Athena's hex: `"base64"`, `"hex"` (as `#[athena(hex)]`) or `"utf8"`, which keeps the bytes
+///   of the value as is. Without it, a `Vec<u8>` is read and written as hex, since that is
+///   how `GetQueryResults` returns `varbinary`.
+///
+/// # Enums
+///
+/// An enum whose variants are all unit variants derives `FromStr` instead, reading each
+/// variant from its name or its `#[athena(rename = "...")]`. Any other value is an error
+/// listing the accepted ones. Such an enum can be the type of a field, or the element of a
+/// `Vec`, `HashSet` or `BTreeSet` field such as a comma separated list of flags.
+///
+/// ```ignore
+/// #[derive(FromAthena)]
+/// enum Permission {
+///     #[athena(rename = "READ")]
+///     Read,
+///     #[athena(rename = "WRITE")]
+///     Write,
+/// }
+/// ```
+#[proc_macro_derive(FromAthena, attributes(athena, serde))]
 pub fn from_athena(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
+    if let syn::Data::Enum(ref data) = input.data {
+        return TokenStream::from(unit_enum::expand_from_str(&input, data));
+    }
+
     if let syn::Data::Struct(ref data) = input.data {
         if let Fields::Named(ref fields) = data.fields {
-            let field_vals = fields.named.iter().enumerate().map(|(_, field)| {
+            let mut field_vals = Vec::new();
+            let mut lenient_vals = Vec::new();
+            let mut columns = Vec::new();
+            let mut positions = Vec::new();
+            let mut column_specs = Vec::new();
+            let mut uses_types = false;
+            let mut errors: Option<syn::Error> = None;
+
+            let container = match ContainerAttrs::from_attrs(&input.attrs) {
+                Ok(container) => container,
+                Err(e) => {
+                    errors = Some(e);
+                    ContainerAttrs::default()
+                }
+            };
+            let struct_name = &input.ident;
+            let error_prefix = match &container.error_prefix {
+                Some(prefix) => quote!(#prefix),
+                None => quote!(stringify!(#struct_name)),
+            };
+
+            let parts = delimited_parts(fields);
+            let flattened = flattened_structs(fields);
+            // A map flatten field collects the columns not read by this struct, nor by the
+            // structs flattened into it.
+            let unread = if flattened.is_empty() {
+                quote!(Self::athena_columns())
+            } else {
+                quote!(&[
+                    Self::athena_columns(),
+                    #(<#flattened as FromAthena>::athena_columns()),*
+                ]
+                .concat())
+            };
+
+            for (field, part) in fields.named.iter().zip(parts) {
                 let name = &field.ident;
                 let ty = &field.ty;
+                let attrs = match FieldAttrs::from_field(field) {
+                    Ok(attrs) => attrs,
+                    Err(e) => {
+                        match errors.as_mut() {
+                            Some(errors) => errors.combine(e),
+                            None => errors = Some(e),
+                        }
+                        continue;
+                    }
+                };
 
-                quote!(#name: row.get(stringify!(#name))
-                    .ok_or(anyhow::Error::msg(format!("Missing field within result set. `{}` was not found!", stringify!(#name))))?
-                .parse::<#ty>()?)
-            });
+                if attrs.flatten {
+                    if !is_json_map(ty) && map_types(ty).is_none() {
+                        uses_types = true;
+                        let parsed = quote!(<#ty as FromAthena>::from_athena_source(row, types)?);
+                        field_vals.push(quote!(#name: #parsed));
+                        let lenient =
+                            lenient_expr(&quote!(stringify!(#name)), &parsed, &error_prefix);
+                        lenient_vals.push(quote!(#name: #lenient));
+                        continue;
+                    }
+
+                    let flattened = if is_json_map(ty) {
+                        uses_types = true;
+                        quote!(aws_athena_parser::field::flatten_json(row, #unread, types))
+                    } else {
+                        quote!(aws_athena_parser::field::flatten(row, #unread))
+                    };
+                    field_vals.push(quote!(#name: #flattened));
+                    lenient_vals.push(quote!(#name: #flattened));
+                    continue;
+                }
+
+                if let Some(prefix) = &attrs.collect_prefix {
+                    let Some(elem) = collection_element(ty).filter(|_| is_vec(ty)) else {
+                        let e = syn::Error::new_spanned(
+                            ty,
+                            "`collect_prefix` requires a `Vec<T>` field",
+                        );
+                        match errors.as_mut() {
+                            Some(errors) => errors.combine(e),
+                            None => errors = Some(e),
+                        }
+                        continue;
+                    };
+                    let collected = quote!(
+                        aws_athena_parser::field::collect_prefixed::<#elem, _>(row, #prefix)?
+                    );
+                    field_vals.push(quote!(#name: #collected));
+                    let lenient = lenient_expr(&quote!(#prefix), &collected, &error_prefix);
+                    lenient_vals.push(quote!(#name: #lenient));
+                    continue;
+                }
+
+                if let Some(prefix) = &attrs.prefix {
+                    let Some((key, val)) = map_types(ty) else {
+                        let e = syn::Error::new_spanned(
+                            ty,
+                            "`prefix` requires a `HashMap<K, V>` or `BTreeMap<K, V>` field",
+                        );
+                        match errors.as_mut() {
+                            Some(errors) => errors.combine(e),
+                            None => errors = Some(e),
+                        }
+                        continue;
+                    };
+                    let collected = quote!(
+                        aws_athena_parser::field::collect_prefixed_map::<#key, #val, _, _>(row, #prefix)?
+                    );
+                    field_vals.push(quote!(#name: #collected));
+                    let lenient = lenient_expr(&quote!(#prefix), &collected, &error_prefix);
+                    lenient_vals.push(quote!(#name: #lenient));
+                    continue;
+                }
+
+                let column = match &part {
+                    Some(part) => {
+                        let source = &part.source;
+                        quote!(#source)
+                    }
+                    None => column_name(field, &attrs, &container),
+                };
+                // Every field of a `flatten_delimited` group reads the same column, so it is
+                // listed once.
+                if part.as_ref().is_none_or(|part| part.index == 0) {
+                    columns.push(column.clone());
+
+                    let required = option_inner(ty).is_none();
+                    let rust_type = match plain_type_name(option_inner(ty).unwrap_or(ty)) {
+                        Some(rust_type) if part.is_none() && !attrs.decides_parsing() => {
+                            quote!(Some(#rust_type))
+                        }
+                        _ => quote!(None),
+                    };
+                    column_specs.push(quote!(aws_athena_parser::ColumnSpec {
+                        name: #column,
+                        rust_type: #rust_type,
+                        required: #required,
+                    }));
+                }
+
+                let lookup = match &attrs.position {
+                    Some(position) => {
+                        let index = positions.len();
+                        positions.push(quote!((#position, #column)));
+                        quote!(aws_athena_parser::field::positional_value(
+                            row, positional, #index, #column,
+                        ))
+                    }
+                    None => quote!(aws_athena_parser::RowSource::get_value(row, #column)),
+                };
+
+                let split = |value: TokenStream2| match &part {
+                    Some(DelimitedPart {
+                        index,
+                        count,
+                        delimiter,
+                        ..
+                    }) => quote!(aws_athena_parser::field::split_part(
+                        #column, #value, #delimiter, #index, #count,
+                    )?),
+                    None => value,
+                };
+
+                if attrs.normalize_zero {
+                    let inner = option_inner(ty).unwrap_or(ty);
+                    if !is_type(inner, "f32") && !is_type(inner, "f64") {
+                        let e = syn::Error::new_spanned(
+                            ty,
+                            "`normalize_zero` requires an `f32` or `f64` field",
+                        );
+                        match errors.as_mut() {
+                            Some(errors) => errors.combine(e),
+                            None => errors = Some(e),
+                        }
+                        continue;
+                    }
+                }
+
+                let parsed = match option_inner(ty) {
+                    Some(inner) => {
+                        let parsed = parse_expr(&column, inner, &attrs, split(quote!(value)));
+                        let parsed = normalize_zero(&attrs, parsed);
+                        let parsed = check_range(&column, &attrs, parsed);
+                        quote!(match #lookup {
+                            Some(value) => Some(#parsed),
+                            None => None,
+                        })
+                    }
+                    None => {
+                        let value = quote!(#lookup
+                            .ok_or_else(|| aws_athena_parser::field::missing_field(#column))?);
+                        let parsed = parse_expr(&column, ty, &attrs, split(value));
+                        let parsed = normalize_zero(&attrs, parsed);
+                        check_range(&column, &attrs, parsed)
+                    }
+                };
+
+                field_vals.push(quote!(#name: #parsed));
+                let lenient = lenient_expr(&column, &parsed, &error_prefix);
+                lenient_vals.push(quote!(#name: #lenient));
+            }
+
+            if let Some(errors) = errors {
+                return TokenStream::from(errors.to_compile_error());
+            }
+
+            let name = &input.ident;
+            let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+            let empty_row = if container.default_on_empty_row {
+                quote!(if Self::athena_columns().iter().all(|column| {
+                    aws_athena_parser::RowSource::get_value(row, column)
+                        .map_or(true, |value| value.is_empty())
+                }) {
+                    return Ok(Default::default());
+                })
+            } else {
+                quote!()
+            };
+            let types = if uses_types {
+                quote!(types)
+            } else {
+                quote!(_types)
+            };
+            let positional = if positions.is_empty() {
+                quote!(_positional)
+            } else {
+                quote!(positional)
+            };
+            let lenient_positional = if positions.is_empty() {
+                quote!()
+            } else {
+                quote!(let positional: &[Option<&str>] = &[];)
+            };
+            let lenient = if container.lenient {
+                quote!(
+                impl #impl_generics #name #ty_generics #where_clause {
+                    /// Converts a row as `from_athena` does, except that a field which fails to
+                    /// parse falls back to `Default::default()` and is reported alongside the
+                    /// result instead of failing the row.
+                    pub fn from_athena_lenient(
+                        row: HashMap<String, String>,
+                    ) -> (Self, Vec<aws_athena_parser::FieldError>) {
+                        Self::from_athena_lenient_with_types(&row, &HashMap::new())
+                    }
+
+                    /// Converts a borrowed row as `from_athena_lenient` does, given the Athena
+                    /// type of each column as for `from_athena_with_types`.
+                    #[allow(clippy::needless_question_mark)]
+                    pub fn from_athena_lenient_with_types(
+                        row: &HashMap<String, String>,
+                        #types: &HashMap<String, String>,
+                    ) -> (Self, Vec<aws_athena_parser::FieldError>) {
+                        #lenient_positional
+                        let mut errors = Vec::new();
+                        let parsed = Self {
+                            #(#lenient_vals),*
+                        };
 
-            let name = input.ident;
+                        (parsed, errors)
+                    }
+                })
+            } else {
+                quote!()
+            };
 
             return TokenStream::from(quote!(
-            impl FromAthena for #name {
+            impl #impl_generics FromAthena for #name #ty_generics #where_clause {
+                #[inline]
                 fn from_athena(row: HashMap<String, String>) -> Result<Self, anyhow::Error> {
-                    Ok(Self {
-                        #(#field_vals),*
-                    })
+                    Self::from_athena_ref(&row, &HashMap::new())
                 }
-            }));
+
+                #[inline]
+                fn from_athena_with_types(
+                    row: HashMap<String, String>,
+                    types: &HashMap<String, String>,
+                ) -> Result<Self, anyhow::Error> {
+                    Self::from_athena_ref(&row, types)
+                }
+
+                #[inline]
+                fn from_athena_ref(
+                    row: &HashMap<String, String>,
+                    types: &HashMap<String, String>,
+                ) -> Result<Self, anyhow::Error> {
+                    Self::from_athena_source(row, types)
+                }
+
+                #[inline]
+                fn from_athena_source<R: aws_athena_parser::RowSource + ?Sized>(
+                    row: &R,
+                    types: &HashMap<String, String>,
+                ) -> Result<Self, anyhow::Error> {
+                    Self::from_athena_positional(row, &[], types)
+                }
+
+                fn from_athena_positional<R: aws_athena_parser::RowSource + ?Sized>(
+                    row: &R,
+                    #positional: &[Option<&str>],
+                    #types: &HashMap<String, String>,
+                ) -> Result<Self, anyhow::Error> {
+                    #empty_row
+                    let parse = || -> Result<Self, anyhow::Error> {
+                        Ok(Self {
+                            #(#field_vals),*
+                        })
+                    };
+
+                    parse().map_err(|e| e.context(#error_prefix))
+                }
+
+                fn athena_columns() -> &'static [&'static str] {
+                    &[#(#columns),*]
+                }
+
+                fn athena_positions() -> &'static [(usize, &'static str)] {
+                    &[#(#positions),*]
+                }
+
+                fn athena_column_specs() -> &'static [aws_athena_parser::ColumnSpec] {
+                    &[#(#column_specs),*]
+                }
+            }
+
+            #lenient
+            ));
         }
     }
 
     TokenStream::from(
         syn::Error::new(
             input.ident.span(),
-            "Only structs with named fields can derive `FromAthena`",
+            "Only structs with named fields and enums with unit variants can derive `FromAthena`",
         )
         .to_compile_error(),
     )
 }
+
+/// Converts a struct into a row of Athena values by implementing the `ToAthena` trait.
+///
+/// This is the inverse of `FromAthena`: each field is rendered as the text Athena would
+/// return for it, so that the matching `FromAthena` implementation parses it back. The same
+/// `#[athena(...)]` field attributes select how each value is rendered.
+///
+/// `Option<T>` fields that are `None` are left out of the row, arrays and maps are rendered
+/// as `[a, b]` and `{a=1, b=2}`, and a `#[athena(flatten)]` field adds each of its entries
+/// as a column. A flattened field that is not a map adds the row of its own `ToAthena`
+/// implementation.
+///
+/// Fields are written in declaration order, so when several fields write the same column,
+/// such as two flattened structs sharing a column, the field declared last wins.
+///
+/// A `#[athena(with = "module")]` field is rendered with `module::to_athena_field`, a
+/// function taking a reference to the field and returning a `String`. Other fields are
+/// rendered with `ToString`.
+///
+/// A `#[athena(rename = "...")]` field is written to the column with the given name, the
+/// same column `FromAthena` reads it from.
+///
+/// An enum with unit variants derives `Display` instead, rendering each variant as the
+/// value its `FromAthena` implementation reads.
+///
+/// # Examples
+///
+/// ```ignore
+/// use aws_athena_parser::{FromAthena, ToAthena};
+///
+/// #[derive(FromAthena, ToAthena)]
+/// struct MyStruct {
+///     field1: String,
+///     field2: i32,
+/// }
+/// ```
+#[proc_macro_derive(ToAthena, attributes(athena, serde))]
+pub fn to_athena(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    TokenStream::from(to_athena::expand(input))
+}
+
+/// Wraps the expression parsing a field so negative zero becomes zero, if the field has
+/// `#[athena(normalize_zero)]`.
+fn normalize_zero(attrs: &FieldAttrs, parsed: TokenStream2) -> TokenStream2 {
+    if attrs.normalize_zero {
+        quote!(aws_athena_parser::field::normalize_zero(#parsed))
+    } else {
+        parsed
+    }
+}
+
+/// Wraps the expression parsing a field in a check of its `min` and `max` bounds, if any.
+fn check_range(column: &TokenStream2, attrs: &FieldAttrs, parsed: TokenStream2) -> TokenStream2 {
+    if attrs.min.is_none() && attrs.max.is_none() {
+        return parsed;
+    }
+
+    let bound = |bound: &Option<attr::Bound>| match bound {
+        Some(bound) => quote!(Some(#bound)),
+        None => quote!(None),
+    };
+    let (min, max) = (bound(&attrs.min), bound(&attrs.max));
+    quote!(aws_athena_parser::field::check_range(#column, #parsed, #min, #max)?)
+}
+
+/// Wraps the expression parsing a field for `from_athena_lenient`, falling back to
+/// `Default::default()` and recording a `FieldError` for `column` when it fails.
+fn lenient_expr(
+    column: &TokenStream2,
+    parsed: &TokenStream2,
+    prefix: &TokenStream2,
+) -> TokenStream2 {
+    quote!(match (|| -> Result<_, anyhow::Error> {
+        Ok(#parsed)
+    })() {
+        Ok(value) => value,
+        Err(error) => {
+            errors.push(aws_athena_parser::FieldError {
+                field: #column,
+                error: error.context(#prefix),
+            });
+            Default::default()
+        }
+    })
+}
+
+/// Builds the expression converting the raw `value` of a column into the field type `ty`.
+fn parse_expr(
+    column: &TokenStream2,
+    ty: &Type,
+    attrs: &FieldAttrs,
+    value: TokenStream2,
+) -> TokenStream2 {
+    let value = match &attrs.trim_matches {
+        Some(chars) => quote!(aws_athena_parser::field::trim_matches(#value, #chars)),
+        None => value,
+    };
+
+    if let Some(module) = &attrs.with {
+        return quote!(#module::from_athena_field(#value)?);
+    }
+
+    if let Some(percent) = &attrs.percent {
+        let fraction = matches!(percent, Percent::Fraction);
+        return quote!(aws_athena_parser::field::parse_percent(#column, #value, #fraction)?);
+    }
+
+    if attrs.bool_from_int {
+        return quote!(aws_athena_parser::field::parse_bool_from_int(#column, #value)?);
+    }
+
+    if let Some(epoch) = &attrs.epoch {
+        let millis = matches!(epoch, Epoch::Millis);
+        return quote!(aws_athena_parser::field::parse_epoch(#column, #value, #millis)?);
+    }
+
+    if attrs.wkt {
+        return quote!(aws_athena_parser::field::parse_wkt(#column, #value)?);
+    }
+
+    if attrs.hex {
+        return quote!(aws_athena_parser::field::parse_hex(#column, #value)?);
+    }
+
+    if let Some(encoding) = &attrs.bytes_encoding {
+        return match encoding {
+            BytesEncoding::Base64 => {
+                quote!(aws_athena_parser::field::parse_base64(#column, #value)?)
+            }
+            BytesEncoding::Hex => quote!(aws_athena_parser::field::parse_hex(#column, #value)?),
+            BytesEncoding::Utf8 => quote!(#value.as_bytes().to_vec()),
+        };
+    }
+
+    if let Some(formats) = &attrs.formats {
+        return quote!(aws_athena_parser::field::parse_with_formats::<#ty>(
+            #column,
+            #value,
+            &[#(#formats),*],
+        )?);
+    }
+
+    if attrs.decimal_separator.is_some() || attrs.thousands_separator.is_some() {
+        let decimal = match &attrs.decimal_separator {
+            Some(decimal) => quote!(#decimal),
+            None => quote!('.'),
+        };
+        let thousands = match &attrs.thousands_separator {
+            Some(thousands) => quote!(Some(#thousands)),
+            None => quote!(None),
+        };
+        return quote!(aws_athena_parser::field::parse_localized_float::<#ty>(
+            #column, #value, #decimal, #thousands,
+        )?);
+    }
+
+    if let Type::Tuple(tuple) = ty {
+        let arity = tuple.elems.len();
+        let delimiter = delimiter_or(&attrs.delimiter, ",");
+        let elems = tuple.elems.iter().enumerate().map(|(index, elem)| {
+            parse_expr(column, elem, &FieldAttrs::default(), quote!(parts[#index]))
+        });
+
+        return quote!({
+            let parts = aws_athena_parser::field::split_tuple(#column, #value, #delimiter, #arity)?;
+            (#(#elems,)*)
+        });
+    }
+
+    if let Some(elem) = collection_element(ty) {
+        if is_vec(ty) && is_type(elem, "u8") {
+            return quote!(aws_athena_parser::field::parse_binary(#column, #value)?);
+        }
+
+        let delimiter = delimiter_or(&attrs.array_delim, ",");
+        if let Some(inner) = option_inner(elem) {
+            let null_token = delimiter_or(&attrs.null_token, "null");
+            return quote!(aws_athena_parser::field::parse_optional_array_with::<#inner, #ty>(
+                #value,
+                #delimiter,
+                #null_token,
+            )?);
+        }
+
+        return quote!(
+            aws_athena_parser::field::parse_array_with::<#elem, #ty>(#value, #delimiter)?
+        );
+    }
+
+    if let Some((key, val)) = map_types(ty) {
+        let entry_delimiter = delimiter_or(&attrs.map_entry_delim, ",");
+        let kv_delimiter = delimiter_or(&attrs.map_kv_delim, "=");
+        return quote!(aws_athena_parser::field::parse_map::<#key, #val, #ty>(
+            #column,
+            #value,
+            #entry_delimiter,
+            #kv_delimiter,
+        )?);
+    }
+
+    if is_type(ty, "uuid::Uuid") {
+        return quote!(aws_athena_parser::field::parse_uuid(#column, #value)?);
+    }
+
+    if is_zoned_date_time(ty) {
+        return quote!(aws_athena_parser::field::parse_zoned(#column, #value)?);
+    }
+
+    if is_type(ty, "std::time::SystemTime") {
+        return quote!(aws_athena_parser::field::parse_system_time(#column, #value)?);
+    }
+
+    if is_type(ty, "std::path::PathBuf") {
+        return quote!(std::path::PathBuf::from(#value));
+    }
+
+    if is_type(ty, "std::string::String") {
+        return quote!(#value.to_string());
+    }
+
+    if is_type(ty, "std::time::Duration") {
+        let millis = matches!(attrs.duration_unit, Some(DurationUnit::Millis));
+        return quote!(aws_athena_parser::field::parse_duration(#column, #value, #millis)?);
+    }
+
+    if is_type(ty, "char") {
+        return quote!(aws_athena_parser::field::parse_char(#column, #value)?);
+    }
+
+    if is_type(ty, "bigdecimal::BigDecimal") {
+        return quote!(aws_athena_parser::field::parse_big_decimal(#column, #value)?);
+    }
+
+    if is_type(ty, "url::Url") {
+        return quote!(aws_athena_parser::field::parse_url(#column, #value)?);
+    }
+
+    if is_integer(ty) {
+        return quote!(aws_athena_parser::field::parse_int::<#ty>(#column, #value)?);
+    }
+
+    quote_spanned!(ty.span()=>
+        <#ty as aws_athena_parser::field::FromAthenaField>::from_athena_field(#value)?
+    )
+}
+
+/// The place of a `#[athena(flatten_delimited)]` field among the fields splitting the same
+/// column.
+struct DelimitedPart {
+    /// The column being split.
+    source: syn::LitStr,
+    /// Which part of the column the field receives.
+    index: usize,
+    /// How many fields split the column.
+    count: usize,
+    /// The delimiter set on any field of the group, or `,`.
+    delimiter: TokenStream2,
+}
+
+/// Returns, for each field in order, its place within a `flatten_delimited` group.
+///
+/// Fields naming the same source column receive consecutive parts of it in declaration
+/// order. Fields with invalid attributes are treated as ungrouped, as their errors are
+/// reported separately.
+fn delimited_parts(fields: &syn::FieldsNamed) -> Vec<Option<DelimitedPart>> {
+    let attrs: Vec<Option<FieldAttrs>> = fields
+        .named
+        .iter()
+        .map(|field| FieldAttrs::from_field(field).ok())
+        .collect();
+    let source_of = |attrs: &Option<FieldAttrs>| {
+        attrs
+            .as_ref()
+            .and_then(|attrs| attrs.flatten_delimited.as_ref())
+            .map(|source| source.value())
+    };
+
+    attrs
+        .iter()
+        .enumerate()
+        .map(|(position, field)| {
+            let source = field.as_ref()?.flatten_delimited.clone()?;
+            let group: Vec<usize> = (0..attrs.len())
+                .filter(|other| source_of(&attrs[*other]) == Some(source.value()))
+                .collect();
+            let delimiter = group
+                .iter()
+                .find_map(|other| attrs[*other].as_ref()?.delimiter.as_ref())
+                .map_or_else(|| quote!(","), |delimiter| quote!(#delimiter));
+
+            Some(DelimitedPart {
+                source,
+                index: group.iter().position(|other| *other == position)?,
+                count: group.len(),
+                delimiter,
+            })
+        })
+        .collect()
+}
+
+/// Returns the column read or written for a field, its `rename` or else the field name
+/// after any serde `rename_all` rule.
+fn column_name(field: &syn::Field, attrs: &FieldAttrs, container: &ContainerAttrs) -> TokenStream2 {
+    match &attrs.rename {
+        Some(rename) => quote!(#rename),
+        None => {
+            // Raw identifiers such as `r#type` read the column `type`.
+            let name = field.ident.as_ref().map(|ident| {
+                let name = ident.unraw().to_string();
+                match container.rename_all {
+                    Some(rule) => rule.apply(&name),
+                    None => name,
+                }
+            });
+            quote!(#name)
+        }
+    }
+}
+
+/// Returns the delimiter given by an attribute, or `default` when the attribute is unset.
+fn delimiter_or(delimiter: &Option<syn::LitStr>, default: &str) -> TokenStream2 {
+    match delimiter {
+        Some(delimiter) => quote!(#delimiter),
+        None => quote!(#default),
+    }
+}
+
+/// Returns the key and value types of a `HashMap<K, V>` or `BTreeMap<K, V>` field.
+fn map_types(ty: &Type) -> Option<(&Type, &Type)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "HashMap" && segment.ident != "BTreeMap" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let mut types = args.args.iter().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+
+    Some((types.next()?, types.next()?))
+}
+
+/// Returns the types of the `#[athena(flatten)]` fields parsed with their own `FromAthena`
+/// implementation rather than collected into a map.
+fn flattened_structs(fields: &syn::FieldsNamed) -> Vec<&Type> {
+    fields
+        .named
+        .iter()
+        .filter(|field| FieldAttrs::from_field(field).is_ok_and(|attrs| attrs.flatten))
+        .map(|field| &field.ty)
+        .filter(|ty| !is_json_map(ty) && map_types(ty).is_none())
+        .collect()
+}
+
+/// Returns whether the field type is a `serde_json::Map<String, Value>`, also written
+/// `Map<String, Value>`.
+fn is_json_map(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    if path.qself.is_some() || !is_path(&path.path, "serde_json::Map") {
+        return false;
+    }
+
+    let Some(PathArguments::AngleBracketed(args)) =
+        path.path.segments.last().map(|segment| &segment.arguments)
+    else {
+        return false;
+    };
+
+    match (args.args.first(), args.args.get(1), args.args.len()) {
+        (Some(GenericArgument::Type(key)), Some(GenericArgument::Type(value)), 2) => {
+            is_type(key, "std::string::String") && is_type(value, "serde_json::Value")
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether the field type is a `DateTime<Tz>`, zoned by `chrono_tz::Tz`.
+fn is_zoned_date_time(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    if path.qself.is_some() || !is_path(&path.path, "chrono::DateTime") {
+        return false;
+    }
+
+    match path.path.segments.last() {
+        Some(segment) => match &segment.arguments {
+            PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(GenericArgument::Type(zone)) => is_type(zone, "chrono_tz::Tz"),
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Returns the last segment of a field type path without generic arguments, such as `i64`.
+fn plain_type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .filter(|segment| segment.arguments.is_empty())
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns whether the field type is one of the primitive integer types.
+fn is_integer(ty: &Type) -> bool {
+    [
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    ]
+    .iter()
+    .any(|name| is_type(ty, name))
+}
+
+/// Returns whether the field type is the type at the full path `name`, such as
+/// `std::time::Duration`, without generic arguments.
+///
+/// Types are only known by how the field spells them, see [`is_path`].
+fn is_type(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) => {
+            path.qself.is_none()
+                && path
+                    .path
+                    .segments
+                    .iter()
+                    .all(|segment| segment.arguments.is_empty())
+                && is_path(&path.path, name)
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether a type path, ignoring generic arguments, names the item at the full
+/// path `name`, such as `std::time::Duration`.
+///
+/// The path may leave out any of the leading segments, as with `Duration` or
+/// `time::Duration`, and `std` may be spelled `core` or `alloc`. A path spelling out
+/// another crate, such as `chrono::Duration`, does not match. Imports cannot be
+/// resolved by a derive, so a different type imported under the same name, such as
+/// `use chrono::Duration` followed by a `Duration` field, is still taken for `name`.
+fn is_path(path: &syn::Path, name: &str) -> bool {
+    let expected: Vec<&str> = name.split("::").collect();
+
+    path.segments.len() <= expected.len()
+        && path
+            .segments
+            .iter()
+            .rev()
+            .zip(expected.iter().rev())
+            .all(|(segment, expected)| {
+                segment.ident == expected
+                    || (*expected == "std" && (segment.ident == "core" || segment.ident == "alloc"))
+            })
+}
+
+/// Returns the inner type of an `Option<T>` field.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(inner)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns whether the field type is a `Vec<T>`.
+fn is_vec(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Vec"),
+        _ => false,
+    }
+}
+
+/// Returns the element type of a `Vec<T>`, `HashSet<T>` or `BTreeSet<T>` field.
+fn collection_element(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+
+    if !["Vec", "HashSet", "BTreeSet"]
+        .iter()
+        .any(|collection| segment.ident == collection)
+    {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(elem)) => Some(elem),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,272 @@
+use crate::attr::{BytesEncoding, ContainerAttrs, DurationUnit, Epoch, FieldAttrs, Percent};
+use crate::unit_enum;
+use crate::{
+    collection_element, column_name, delimited_parts, delimiter_or, is_json_map, is_type, is_vec,
+    is_zoned_date_time, map_types, option_inner,
+};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{DeriveInput, Fields, Type};
+
+/// Generates the `ToAthena` implementation of a struct with named fields, or the `Display`
+/// implementation of an enum with unit variants.
+pub fn expand(input: DeriveInput) -> TokenStream2 {
+    if let syn::Data::Enum(ref data) = input.data {
+        return unit_enum::expand_display(&input, data);
+    }
+
+    let syn::Data::Struct(ref data) = input.data else {
+        return unsupported(&input);
+    };
+    let Fields::Named(ref fields) = data.fields else {
+        return unsupported(&input);
+    };
+
+    let mut inserts = Vec::new();
+    let mut errors = None;
+    let container = match ContainerAttrs::from_attrs(&input.attrs) {
+        Ok(container) => container,
+        Err(e) => {
+            errors = Some(e);
+            ContainerAttrs::default()
+        }
+    };
+
+    let mut groups: Vec<(syn::LitStr, TokenStream2, Vec<TokenStream2>)> = Vec::new();
+
+    for (field, part) in fields.named.iter().zip(delimited_parts(fields)) {
+        let name = &field.ident;
+        let ty = &field.ty;
+        let attrs = match FieldAttrs::from_field(field) {
+            Ok(attrs) => attrs,
+            Err(e) => {
+                match errors.as_mut() {
+                    Some(errors) => errors.combine(e),
+                    None => errors = Some(e),
+                }
+                continue;
+            }
+        };
+
+        if attrs.flatten && !is_json_map(ty) && map_types(ty).is_none() {
+            inserts.push(quote!(row.extend(ToAthena::to_athena(&self.#name));));
+            continue;
+        }
+
+        if attrs.flatten {
+            inserts.push(quote!(row.extend(
+                self.#name
+                    .iter()
+                    .map(|(column, value)| (column.to_string(), value.to_string())),
+            );));
+            continue;
+        }
+
+        if let Some(prefix) = &attrs.collect_prefix {
+            let formatted = match collection_element(ty) {
+                Some(elem) => format_expr(elem, &FieldAttrs::default(), quote!(value)),
+                None => quote!(value.to_string()),
+            };
+            inserts.push(quote!(for (index, value) in self.#name.iter().enumerate() {
+                row.insert(format!("{}{}", #prefix, index + 1), #formatted);
+            }));
+            continue;
+        }
+
+        if let Some(prefix) = &attrs.prefix {
+            let (key, val) = match map_types(ty) {
+                Some((key, val)) => (
+                    format_expr(key, &FieldAttrs::default(), quote!(key)),
+                    format_expr(val, &FieldAttrs::default(), quote!(value)),
+                ),
+                None => (quote!(key.to_string()), quote!(value.to_string())),
+            };
+            inserts.push(quote!(for (key, value) in self.#name.iter() {
+                row.insert(format!("{}{}", #prefix, #key), #val);
+            }));
+            continue;
+        }
+
+        if let Some(part) = part {
+            let formatted = match option_inner(ty) {
+                Some(inner) => {
+                    let formatted = format_expr(inner, &attrs, quote!(value));
+                    quote!(match &self.#name {
+                        Some(value) => #formatted,
+                        None => String::new(),
+                    })
+                }
+                None => format_expr(ty, &attrs, quote!((&self.#name))),
+            };
+            match groups
+                .iter_mut()
+                .find(|(source, ..)| source.value() == part.source.value())
+            {
+                Some((.., parts)) => parts.push(formatted),
+                None => groups.push((part.source, part.delimiter, vec![formatted])),
+            }
+            continue;
+        }
+
+        let column = column_name(field, &attrs, &container);
+
+        if let Some(inner) = option_inner(ty) {
+            let formatted = format_expr(inner, &attrs, quote!(value));
+            inserts.push(quote!(if let Some(value) = &self.#name {
+                row.insert(#column.to_string(), #formatted);
+            }));
+            continue;
+        }
+
+        let formatted = format_expr(ty, &attrs, quote!((&self.#name)));
+        inserts.push(quote!(row.insert(#column.to_string(), #formatted);));
+    }
+
+    if let Some(errors) = errors {
+        return errors.to_compile_error();
+    }
+
+    for (source, delimiter, parts) in groups {
+        inserts.push(quote!(row.insert(#source.to_string(), [#(#parts),*].join(#delimiter));));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote!(
+    impl #impl_generics ToAthena for #name #ty_generics #where_clause {
+        fn to_athena(&self) -> HashMap<String, String> {
+            let mut row = HashMap::new();
+            #(#inserts)*
+            row
+        }
+    })
+}
+
+/// Builds the expression rendering `value`, a reference to a field of type `ty`, as the text
+/// the matching `FromAthena` field would parse.
+fn format_expr(ty: &Type, attrs: &FieldAttrs, value: TokenStream2) -> TokenStream2 {
+    if let Some(module) = &attrs.with {
+        return quote!(#module::to_athena_field(#value));
+    }
+
+    if let Some(percent) = &attrs.percent {
+        let fraction = matches!(percent, Percent::Fraction);
+        return quote!(aws_athena_parser::field::format_percent(*#value, #fraction));
+    }
+
+    if attrs.bool_from_int {
+        return quote!(u8::from(*#value).to_string());
+    }
+
+    if let Some(epoch) = &attrs.epoch {
+        return match epoch {
+            Epoch::Seconds => quote!(#value.timestamp().to_string()),
+            Epoch::Millis => quote!(#value.timestamp_millis().to_string()),
+        };
+    }
+
+    if attrs.wkt {
+        return quote!(aws_athena_parser::field::format_wkt(#value));
+    }
+
+    if attrs.hex {
+        return quote!(aws_athena_parser::field::format_hex(#value));
+    }
+
+    if let Some(encoding) = &attrs.bytes_encoding {
+        return match encoding {
+            BytesEncoding::Base64 => quote!(aws_athena_parser::field::format_base64(#value)),
+            BytesEncoding::Hex => quote!(aws_athena_parser::field::format_hex(#value)),
+            BytesEncoding::Utf8 => quote!(String::from_utf8_lossy(#value).into_owned()),
+        };
+    }
+
+    if let Some(formats) = &attrs.formats {
+        let format = &formats[0];
+        return quote!(#value.format(#format).to_string());
+    }
+
+    if let Some(decimal) = &attrs.decimal_separator {
+        return quote!(#value.to_string().replace('.', &#decimal.to_string()));
+    }
+
+    if let Type::Tuple(tuple) = ty {
+        let delimiter = delimiter_or(&attrs.delimiter, ",");
+        let elems = tuple.elems.iter().enumerate().map(|(index, elem)| {
+            let index = syn::Index::from(index);
+            format_expr(elem, &FieldAttrs::default(), quote!((&#value.#index)))
+        });
+
+        return quote!([#(#elems),*].join(#delimiter));
+    }
+
+    if let Some(elem) = collection_element(ty) {
+        if is_vec(ty) && is_type(elem, "u8") {
+            return quote!(aws_athena_parser::field::format_hex(#value));
+        }
+
+        let delimiter = match &attrs.array_delim {
+            Some(delimiter) => quote!(#delimiter),
+            None => quote!(", "),
+        };
+        let formatted = match option_inner(elem) {
+            Some(inner) => {
+                let formatted = format_expr(inner, &FieldAttrs::default(), quote!(value));
+                let null_token = delimiter_or(&attrs.null_token, "null");
+                quote!(match value {
+                    Some(value) => #formatted,
+                    None => #null_token.to_string(),
+                })
+            }
+            None => format_expr(elem, &FieldAttrs::default(), quote!(value)),
+        };
+        return quote!(aws_athena_parser::field::format_array(
+            #value.iter().map(|value| #formatted),
+            #delimiter,
+        ));
+    }
+
+    if let Some((key, val)) = map_types(ty) {
+        let entry_delimiter = match &attrs.map_entry_delim {
+            Some(delimiter) => quote!(#delimiter),
+            None => quote!(", "),
+        };
+        let kv_delimiter = delimiter_or(&attrs.map_kv_delim, "=");
+        let key = format_expr(key, &FieldAttrs::default(), quote!(key));
+        let val = format_expr(val, &FieldAttrs::default(), quote!(value));
+        return quote!(aws_athena_parser::field::format_map(
+            #value.iter().map(|(key, value)| (#key, #val)),
+            #entry_delimiter,
+            #kv_delimiter,
+        ));
+    }
+
+    if is_zoned_date_time(ty) {
+        return quote!(aws_athena_parser::field::format_zoned(#value));
+    }
+
+    if is_type(ty, "std::time::SystemTime") {
+        return quote!(aws_athena_parser::field::format_system_time(#value));
+    }
+
+    if is_type(ty, "std::time::Duration") {
+        return match attrs.duration_unit {
+            Some(DurationUnit::Millis) => quote!(#value.as_millis().to_string()),
+            _ => quote!(#value.as_secs_f64().to_string()),
+        };
+    }
+
+    if is_type(ty, "std::path::PathBuf") {
+        return quote!(#value.display().to_string());
+    }
+
+    quote!(#value.to_string())
+}
+
+fn unsupported(input: &DeriveInput) -> TokenStream2 {
+    syn::Error::new(
+        input.ident.span(),
+        "Only structs with named fields and enums with unit variants can derive `ToAthena`",
+    )
+    .to_compile_error()
+}
@@ -0,0 +1,108 @@
+use crate::attr::variant_rename;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{DataEnum, DeriveInput, Fields, Ident, LitStr};
+
+/// Generates the `FromStr` implementation of an enum with unit variants, for
+/// `#[derive(FromAthena)]`.
+pub fn expand_from_str(input: &DeriveInput, data: &DataEnum) -> TokenStream2 {
+    let variants = match variants(data, "FromAthena") {
+        Ok(variants) => variants,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let idents = variants.iter().map(|(ident, _)| ident);
+    let tokens = variants.iter().map(|(_, token)| token);
+    let expected = expected(&variants);
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote!(
+    impl #impl_generics std::str::FromStr for #name #ty_generics #where_clause {
+        type Err = anyhow::Error;
+
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            match value {
+                #(#tokens => Ok(Self::#idents),)*
+                _ => Err(anyhow::Error::msg(format!(
+                    "Invalid {} within result set. `{}` is not one of {}!",
+                    stringify!(#name),
+                    value,
+                    #expected,
+                ))),
+            }
+        }
+    })
+}
+
+/// Generates the `Display` implementation of an enum with unit variants, for
+/// `#[derive(ToAthena)]`, rendering each variant as the token `FromStr` reads.
+pub fn expand_display(input: &DeriveInput, data: &DataEnum) -> TokenStream2 {
+    let variants = match variants(data, "ToAthena") {
+        Ok(variants) => variants,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let idents = variants.iter().map(|(ident, _)| ident);
+    let tokens = variants.iter().map(|(_, token)| token);
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote!(
+    impl #impl_generics std::fmt::Display for #name #ty_generics #where_clause {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(match self {
+                #(Self::#idents => #tokens,)*
+            })
+        }
+    })
+}
+
+/// Returns each variant with the token it is read from, its `rename` or else its name
+/// without any `r#` prefix.
+fn variants(data: &DataEnum, derive: &str) -> syn::Result<Vec<(Ident, LitStr)>> {
+    let mut errors: Option<syn::Error> = None;
+    let mut variants = Vec::new();
+
+    for variant in &data.variants {
+        let token = match variant.fields {
+            Fields::Unit => variant_rename(&variant.attrs),
+            _ => Err(syn::Error::new_spanned(
+                variant,
+                format!("Only enums with unit variants can derive `{}`", derive),
+            )),
+        };
+        match token {
+            Ok(token) => {
+                let token = token.unwrap_or_else(|| {
+                    LitStr::new(&variant.ident.unraw().to_string(), variant.ident.span())
+                });
+                variants.push((variant.ident.clone(), token));
+            }
+            Err(e) => match errors.as_mut() {
+                Some(errors) => errors.combine(e),
+                None => errors = Some(e),
+            },
+        }
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(variants),
+    }
+}
+
+/// Lists the accepted tokens for an error message, such as "`READ`, `WRITE` or `EXEC`".
+fn expected(variants: &[(Ident, LitStr)]) -> String {
+    let tokens: Vec<String> = variants
+        .iter()
+        .map(|(_, token)| format!("`{}`", token.value()))
+        .collect();
+
+    match tokens.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+        Some((last, _)) => last.clone(),
+        None => "no value".to_string(),
+    }
+}